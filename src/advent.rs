@@ -1,42 +1,86 @@
-use reqwest::blocking::Client;
 use std::{
+    fmt,
     fs::File,
-    io::{BufRead, BufReader, Read, Write},
+    io::{BufRead, BufReader},
+    path::Path,
 };
 
-fn input_file_path(day: i32) -> String {
-    format!("inputs/{:0>2}.txt", day)
+/// Whatever a part spits out. Most days are numbers, but some spell out words (or pictures).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
 }
 
-fn get_secret() -> String {
-    let mut f = File::open(".cookie").unwrap();
-    let mut buffer = String::new();
-    f.read_to_string(&mut buffer).unwrap();
-    buffer
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Number(n as i128)
+            }
+        })*
+    };
+}
+answer_from_number!(i32, u32, i64, u64, usize);
+
+#[cfg(feature = "bigint")]
+impl From<rug::Integer> for Answer {
+    fn from(n: rug::Integer) -> Self {
+        match n.to_i128() {
+            Some(n) => Answer::Number(n),
+            None => Answer::Text(n.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
 }
 
-fn write_input_file(day: i32, buffer: &str) {
-    let mut f = File::create(input_file_path(day)).unwrap();
-    f.write_all(buffer.as_bytes()).unwrap();
+/// Solve one part of a puzzle, given the lines of the input file.
+pub type Part = fn(&[String]) -> Answer;
+
+pub const DEFAULT_YEAR: i32 = 2022;
+
+pub fn read_input_file(path: impl AsRef<Path>) -> Result<Vec<String>, String> {
+    let path = path.as_ref();
+    let f = File::open(path).map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+    Ok(BufReader::new(f).lines().map(Result::unwrap).collect())
 }
 
-fn download_input(day: i32) {
-    let secret = get_secret();
-    let client = Client::new();
-    let text = client
-        .get(format!("https://adventofcode.com/2022/day/{}/input", day))
-        .header("Cookie", format!("session={}", secret))
-        .send()
-        .unwrap()
-        .text()
-        .unwrap();
-    write_input_file(day, &text);
+#[cfg(test)]
+pub fn read_example(day: i32) -> Vec<String> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("examples")
+        .join(format!("{:0>2}.txt", day));
+    read_input_file(path).unwrap()
 }
 
-pub fn read_input(day: i32) -> Vec<String> {
-    if !std::path::Path::new(&input_file_path(day)).exists() {
-        download_input(day);
+/// Input lines written out in a test, the way `read_input_file` would read them.
+#[cfg(test)]
+pub fn lines(text: &str) -> Vec<String> {
+    text.lines().map(str::to_string).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::Answer;
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(24000).to_string(), "24000");
+        assert_eq!(Answer::from(-3i64).to_string(), "-3");
+        assert_eq!(Answer::from("CMZ".to_string()).to_string(), "CMZ");
     }
-    let f = File::open(input_file_path(day)).unwrap();
-    BufReader::new(f).lines().map(Result::unwrap).collect()
 }
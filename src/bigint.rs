@@ -0,0 +1,38 @@
+// Numbers for the days that overflow machine integers on big enough inputs (11, 20, 21 and 25).
+// They're plain `i64`/`u64` normally, and arbitrary precision with the `bigint` feature.
+//
+// Days using these have to stick to what both kinds of number can do: owned arithmetic,
+// comparing with literals, `parse` and `Display`. That means `.clone()`s which clippy finds
+// pointless on the machine integers, so those days allow `clippy::clone_on_copy`.
+
+#[cfg(feature = "bigint")]
+pub type Signed = rug::Integer;
+#[cfg(feature = "bigint")]
+pub type Unsigned = rug::Integer;
+
+#[cfg(not(feature = "bigint"))]
+pub type Signed = i64;
+#[cfg(not(feature = "bigint"))]
+pub type Unsigned = u64;
+
+/// The remainder of dividing by a small number, which is never negative.
+#[cfg(feature = "bigint")]
+pub fn rem_euclid(n: &Signed, divisor: u32) -> u32 {
+    n.mod_u(divisor)
+}
+
+/// The remainder of dividing by a small number, which is never negative.
+#[cfg(not(feature = "bigint"))]
+pub fn rem_euclid(n: &Signed, divisor: u32) -> u32 {
+    n.rem_euclid(divisor as i64) as u32
+}
+
+#[cfg(feature = "bigint")]
+pub fn is_multiple_of(n: &Unsigned, divisor: &Unsigned) -> bool {
+    n.is_divisible(divisor)
+}
+
+#[cfg(not(feature = "bigint"))]
+pub fn is_multiple_of(n: &Unsigned, divisor: &Unsigned) -> bool {
+    n.is_multiple_of(*divisor)
+}
@@ -0,0 +1,307 @@
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+
+#[derive(Parser, Debug)]
+#[command(about = "Solutions for https://adventofcode.com/2022", version)]
+pub struct Cli {
+    /// Show more diagnostics on stderr (-v, -vv, -vvv)
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+    /// Show fewer diagnostics on stderr (-q hides warnings, -qq hides errors too)
+    #[arg(short, long, action = ArgAction::Count, global = true, conflicts_with = "verbose")]
+    pub quiet: u8,
+    /// Read settings from this file instead of the nearest aoc.toml
+    #[arg(long, global = true)]
+    pub config: Option<String>,
+    /// Which year to talk to adventofcode.com about [default: 2022]
+    #[arg(long, global = true)]
+    pub year: Option<i32>,
+    /// Where downloaded inputs are kept [default: inputs]
+    #[arg(long, global = true)]
+    pub inputs_dir: Option<String>,
+    /// Where the puzzle examples are kept [default: examples]
+    #[arg(long, global = true)]
+    pub examples_dir: Option<String>,
+    /// The file holding the adventofcode.com session cookie [default: .cookie]
+    #[arg(long, global = true)]
+    pub cookie_file: Option<String>,
+    /// How many threads to solve with, defaults to one per CPU
+    #[arg(short, long, global = true, value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+impl Cli {
+    pub fn verbosity(&self) -> i32 {
+        self.verbose as i32 - self.quiet as i32
+    }
+
+    /// The global options as they were given, to pass on to another run of this tool.
+    pub fn global_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if self.verbose > 0 {
+            args.push(format!("-{}", "v".repeat(self.verbose as usize)));
+        }
+        if self.quiet > 0 {
+            args.push(format!("-{}", "q".repeat(self.quiet as usize)));
+        }
+        let options = [
+            ("--config", self.config.clone()),
+            ("--year", self.year.map(|year| year.to_string())),
+            ("--inputs-dir", self.inputs_dir.clone()),
+            ("--examples-dir", self.examples_dir.clone()),
+            ("--cookie-file", self.cookie_file.clone()),
+            ("--jobs", self.jobs.map(|jobs| jobs.to_string())),
+        ];
+        for (name, value) in options {
+            if let Some(value) = value {
+                args.extend([name.to_string(), value]);
+            }
+        }
+        args
+    }
+}
+
+fn day_parser() -> clap::builder::RangedI64ValueParser<i32> {
+    clap::value_parser!(i32).range(1..=25)
+}
+
+// any day can be scaffolded, not just the ones that are out
+fn new_day_parser() -> clap::builder::RangedI64ValueParser<i32> {
+    clap::value_parser!(i32).range(1..)
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Solve a day, or every day if none is given
+    Run {
+        #[arg(value_parser = day_parser())]
+        day: Option<i32>,
+        /// Don't add the results to the run history
+        #[arg(long)]
+        no_history: bool,
+        /// Solve one part at a time, so their timings don't skew each other
+        #[arg(long)]
+        serial: bool,
+        #[command(flatten)]
+        solve: SolveArgs,
+    },
+    /// Time a day's solutions over several iterations
+    Bench {
+        #[arg(value_parser = day_parser())]
+        day: Option<i32>,
+        /// How many times to run each part
+        #[arg(short = 'n', long, default_value_t = 10)]
+        iterations: u32,
+        #[command(flatten)]
+        solve: SolveArgs,
+    },
+    /// Check a day's answers against the ones recorded next to its input
+    Verify {
+        #[arg(value_parser = day_parser())]
+        day: Option<i32>,
+        /// Check the puzzle examples in examples/ instead of the real inputs
+        #[arg(short, long)]
+        example: bool,
+        /// Record the current answers as the expected ones instead of checking them
+        #[arg(long)]
+        record: bool,
+        #[command(flatten)]
+        solve: SolveArgs,
+    },
+    /// Re-run a day whenever its source, example or input changes
+    Watch {
+        #[arg(value_parser = day_parser())]
+        day: i32,
+        /// Read the puzzle input from this file instead of inputs/
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Download a day's input into inputs/, replacing any cached copy that changed
+    Download {
+        #[arg(value_parser = day_parser(), required_unless_present = "all")]
+        day: Option<i32>,
+        /// Download every day that's out so far
+        #[arg(long, conflicts_with = "day")]
+        all: bool,
+    },
+    /// Show how a day's answers and timings changed over past runs
+    History {
+        #[arg(value_parser = day_parser())]
+        day: Option<i32>,
+        /// Only show this part
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..))]
+        part: Option<u8>,
+        /// How many of the latest runs to show for each part
+        #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        limit: usize,
+    },
+    /// Check the downloaded inputs for empty, cut-off or error page files
+    CheckInputs,
+    /// Show a private leaderboard's standings and how long everyone took each day
+    Leaderboard {
+        /// The leaderboard's id, the number at the end of its URL
+        id: Option<u64>,
+        /// Read the leaderboard's JSON from this file instead of downloading it
+        #[arg(long, conflicts_with = "id")]
+        file: Option<String>,
+        /// Only show the times for this day
+        #[arg(short, long, value_parser = day_parser())]
+        day: Option<i32>,
+    },
+    /// Submit an answer, solving the part first if no answer is given
+    Submit {
+        #[arg(value_parser = day_parser())]
+        day: i32,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        answer: Option<String>,
+        /// Read the puzzle input from this file instead of inputs/
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Find the elves carrying the most in a day 1 inventory of any size, reading it as it goes
+    TopElves {
+        /// The inventory to read, stdin if not given
+        file: Option<String>,
+        /// How many elves to find
+        #[arg(short, default_value_t = 3)]
+        k: usize,
+    },
+    /// Find where day 6 markers end in a datastream of any size, reading it as it goes (every
+    /// byte counts, newlines too)
+    Markers {
+        /// The datastream to read, stdin if not given
+        file: Option<String>,
+        /// How many different bytes in a row make a marker
+        #[arg(short, long, default_value_t = 4)]
+        size: usize,
+        /// Report every marker, not just the first
+        #[arg(short, long)]
+        all: bool,
+    },
+    /// Save what the renderers draw now as the expected snapshots
+    UpdateSnapshots,
+    /// Create a source file for a new day from a template
+    New {
+        #[arg(value_parser = new_day_parser())]
+        day: i32,
+    },
+    /// Explore a day's parsed input interactively
+    Repl {
+        #[arg(value_parser = day_parser())]
+        day: i32,
+        /// Read the puzzle input from this file instead of inputs/
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct SolveArgs {
+    /// Only solve this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..))]
+    pub part: Option<u8>,
+    /// Read the puzzle input from this file instead of inputs/ (requires a day)
+    #[arg(short, long)]
+    pub input: Option<String>,
+    /// How to print the results [default: text]
+    #[arg(short, long, value_enum)]
+    pub format: Option<Format>,
+    /// Report the peak memory and number of allocations for each part (solves one part at a time)
+    #[arg(long)]
+    pub profile_mem: bool,
+}
+
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Cli, Command, Format};
+    use clap::{CommandFactory, Parser};
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_run() {
+        let cli =
+            Cli::try_parse_from(["aoc", "-vv", "run", "7", "--part", "2", "-f", "json", "-j4"])
+                .unwrap();
+        assert_eq!(cli.verbosity(), 2);
+        assert_eq!(cli.jobs, Some(4));
+        match cli.command {
+            Command::Run { day, solve, .. } => {
+                assert_eq!(day, Some(7));
+                assert_eq!(solve.part, Some(2));
+                assert_eq!(solve.format, Some(Format::Json));
+            }
+            command => panic!("parsed the wrong command: {:?}", command),
+        }
+    }
+
+    #[test]
+    fn test_global_args() {
+        let args = [
+            "aoc",
+            "watch",
+            "7",
+            "-vv",
+            "--config",
+            "other.toml",
+            "--year",
+            "2021",
+            "-j",
+            "2",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        let global_args = cli.global_args();
+        assert_eq!(
+            global_args,
+            [
+                "-vv",
+                "--config",
+                "other.toml",
+                "--year",
+                "2021",
+                "--jobs",
+                "2"
+            ]
+        );
+        // they mean the same thing to the new run
+        let again = Cli::try_parse_from(
+            ["aoc"]
+                .into_iter()
+                .chain(global_args.iter().map(String::as_str))
+                .chain(["run"]),
+        )
+        .unwrap();
+        assert_eq!(again.global_args(), global_args);
+        assert!(Cli::try_parse_from(["aoc", "run"])
+            .unwrap()
+            .global_args()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Cli::try_parse_from(["aoc", "run", "26"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "run", "seven"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "submit", "1", "3"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "-v", "-q", "run"]).is_err());
+        assert!(Cli::try_parse_from(["aoc"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "run", "--jobs", "0"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "download"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "download", "3", "--all"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "new", "0"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "new", "26"]).is_ok());
+    }
+}
@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::advent;
+use crate::cli::{Cli, Format};
+
+pub const FILE_NAME: &str = "aoc.toml";
+
+/// Settings for the harness, from the nearest aoc.toml with the command line taking precedence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub year: i32,
+    pub inputs_dir: PathBuf,
+    pub examples_dir: PathBuf,
+    pub cookie_file: PathBuf,
+    /// Where every `run` appends its answers and timings
+    pub history_file: PathBuf,
+    pub jobs: Option<u16>,
+    pub format: Format,
+    /// Where to download inputs from and submit answers to, without a trailing slash
+    pub base_url: String,
+    /// Added to the User-Agent so the site's admins know who to talk to
+    pub contact: Option<String>,
+    /// The id of the private leaderboard to show by default
+    pub leaderboard: Option<u64>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            year: advent::DEFAULT_YEAR,
+            inputs_dir: PathBuf::from("inputs"),
+            examples_dir: PathBuf::from("examples"),
+            cookie_file: PathBuf::from(".cookie"),
+            history_file: PathBuf::from("history.jsonl"),
+            jobs: None,
+            format: Format::Text,
+            base_url: "https://adventofcode.com".to_string(),
+            contact: None,
+            leaderboard: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn input_file_path(&self, day: i32) -> PathBuf {
+        self.inputs_dir.join(format!("{:0>2}.txt", day))
+    }
+
+    /// The puzzle's worked example, which is checked into the repo alongside its answers.
+    pub fn example_file_path(&self, day: i32) -> PathBuf {
+        self.examples_dir.join(format!("{:0>2}.txt", day))
+    }
+}
+
+/// What can go in aoc.toml. Everything is optional.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct ConfigFile {
+    year: Option<i32>,
+    inputs_dir: Option<PathBuf>,
+    examples_dir: Option<PathBuf>,
+    cookie_file: Option<PathBuf>,
+    history_file: Option<PathBuf>,
+    jobs: Option<u16>,
+    format: Option<Format>,
+    base_url: Option<String>,
+    contact: Option<String>,
+    leaderboard: Option<u64>,
+}
+
+/// Look for aoc.toml in `dir` and then each of its parents.
+pub fn find(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// Relative paths in a config file are relative to the directory it's in,
+/// so the harness finds the same files from anywhere in the project.
+fn parse(text: &str, base_dir: &Path) -> Result<Config, String> {
+    let file: ConfigFile = toml::from_str(text).map_err(|e| e.to_string())?;
+    if file.jobs == Some(0) {
+        return Err("jobs has to be at least 1".to_string());
+    }
+    let defaults = Config::default();
+    let resolve = |path: Option<PathBuf>, default: PathBuf| base_dir.join(path.unwrap_or(default));
+    Ok(Config {
+        year: file.year.unwrap_or(defaults.year),
+        inputs_dir: resolve(file.inputs_dir, defaults.inputs_dir),
+        examples_dir: resolve(file.examples_dir, defaults.examples_dir),
+        cookie_file: resolve(file.cookie_file, defaults.cookie_file),
+        history_file: resolve(file.history_file, defaults.history_file),
+        jobs: file.jobs,
+        format: file.format.unwrap_or(defaults.format),
+        base_url: file
+            .base_url
+            .map(|url| url.trim_end_matches('/').to_string())
+            .unwrap_or(defaults.base_url),
+        contact: file.contact,
+        leaderboard: file.leaderboard,
+    })
+}
+
+fn read(path: &Path) -> Result<Config, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    parse(&text, base_dir).map_err(|e| format!("Bad config in {}: {}", path.display(), e))
+}
+
+/// Read the config given with --config, or the nearest aoc.toml, then apply the command line on top.
+pub fn load(cli: &Cli) -> Result<Config, String> {
+    let path = match &cli.config {
+        Some(path) => Some(PathBuf::from(path)),
+        None => std::env::current_dir().ok().and_then(|dir| find(&dir)),
+    };
+    let mut config = match path {
+        Some(path) => {
+            log::debug!("Using config from {}", path.display());
+            read(&path)?
+        }
+        None => Config::default(),
+    };
+    if let Some(year) = cli.year {
+        config.year = year;
+    }
+    if let Some(dir) = &cli.inputs_dir {
+        config.inputs_dir = PathBuf::from(dir);
+    }
+    if let Some(dir) = &cli.examples_dir {
+        config.examples_dir = PathBuf::from(dir);
+    }
+    if let Some(path) = &cli.cookie_file {
+        config.cookie_file = PathBuf::from(path);
+    }
+    if cli.jobs.is_some() {
+        config.jobs = cli.jobs;
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find, load, parse, Config, FILE_NAME};
+    use crate::cli::{Cli, Format};
+    use clap::Parser;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_parse() {
+        let config = parse(
+            "year = 2021\ninputs-dir = \"puzzles\"\ncookie-file = \"/secret/aoc\"\njobs = 2\nformat = \"json\"\n\
+             base-url = \"http://localhost:8080/\"\ncontact = \"elf@example.com\"\n",
+            Path::new("/home/elf/aoc"),
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            Config {
+                year: 2021,
+                inputs_dir: PathBuf::from("/home/elf/aoc/puzzles"),
+                examples_dir: PathBuf::from("/home/elf/aoc/examples"),
+                cookie_file: PathBuf::from("/secret/aoc"),
+                history_file: PathBuf::from("/home/elf/aoc/history.jsonl"),
+                jobs: Some(2),
+                format: Format::Json,
+                base_url: "http://localhost:8080".to_string(),
+                contact: Some("elf@example.com".to_string()),
+                leaderboard: None,
+            }
+        );
+        assert_eq!(
+            config.input_file_path(7),
+            PathBuf::from("/home/elf/aoc/puzzles/07.txt")
+        );
+        assert!(parse("yaer = 2021", Path::new("")).is_err());
+        assert!(parse("jobs = 0", Path::new("")).is_err());
+        assert!(parse("format = \"xml\"", Path::new("")).is_err());
+    }
+
+    #[test]
+    fn test_find_and_override() {
+        let root = std::env::temp_dir().join(format!("aoc-config-test-{}", std::process::id()));
+        let nested = root.join("src").join("deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join(FILE_NAME), "year = 2020\njobs = 3\n").unwrap();
+        assert_eq!(find(&nested), Some(root.join(FILE_NAME)));
+
+        let path = root.join(FILE_NAME);
+        let cli = Cli::try_parse_from([
+            "aoc",
+            "--config",
+            path.to_str().unwrap(),
+            "--year",
+            "2019",
+            "--inputs-dir",
+            "elsewhere",
+            "run",
+        ])
+        .unwrap();
+        let config = load(&cli).unwrap();
+        assert_eq!(config.year, 2019);
+        assert_eq!(config.jobs, Some(3));
+        assert_eq!(config.inputs_dir, PathBuf::from("elsewhere"));
+        assert_eq!(config.examples_dir, root.join("examples"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
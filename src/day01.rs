@@ -1,39 +1,362 @@
-use crate::advent;
+use std::{cmp::Reverse, collections::BinaryHeap, io::BufRead, ops::RangeInclusive};
 
-pub fn solve() {
-    // reader.fold(Collectifier::new(), |a, b| a.fold(b.unwrap()));
-    let elves = advent::read_input(1)
-        .iter()
-        .fold(vec![0], |mut elves, calorie| {
-            if calorie == "" {
-                elves.push(0);
-            } else {
-                let new_calories = elves.pop().unwrap() + str::parse::<i32>(&calorie).unwrap();
-                elves.push(new_calories);
+use crate::repl;
+
+/// Elf totals from lines read one at a time, so an inventory never has to fit in memory.
+/// Elves are separated by blank lines, and carry one item per line.
+pub struct Totals<I> {
+    lines: I,
+}
+
+impl<I, S> Totals<I>
+where
+    I: Iterator<Item = Result<S, String>>,
+    S: AsRef<str>,
+{
+    pub fn new(lines: I) -> Totals<I> {
+        Totals { lines }
+    }
+}
+
+impl<I, S> Iterator for Totals<I>
+where
+    I: Iterator<Item = Result<S, String>>,
+    S: AsRef<str>,
+{
+    type Item = Result<u64, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut total: Option<u64> = None;
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            let line = line.as_ref();
+            if line.is_empty() {
+                // several blank lines in a row don't make empty elves
+                if total.is_some() {
+                    break;
+                }
+                continue;
             }
-            elves
-        });
+            let calories = match line.parse::<u64>() {
+                Ok(calories) => calories,
+                Err(_) => return Some(Err(format!("Not a number of calories: {}", line))),
+            };
+            match total.unwrap_or(0).checked_add(calories) {
+                Some(sum) => total = Some(sum),
+                None => return Some(Err("An elf carries too many calories to count".to_string())),
+            }
+        }
+        total.map(Ok)
+    }
+}
+
+/// The elves carrying the most so far, as elves are added one at a time. Only `k` of them
+/// are ever kept, however many are added.
+pub struct TopK {
+    k: usize,
+    // the lowest total is on top, so it's the first to go, and among equal totals the elf
+    // listed last goes first
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+    elves: usize,
+}
+
+impl TopK {
+    pub fn new(k: usize) -> TopK {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+            elves: 0,
+        }
+    }
+
+    /// Add the next elf's total.
+    pub fn push(&mut self, total: u64) {
+        self.heap.push(Reverse((total, Reverse(self.elves))));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+        self.elves += 1;
+    }
+
+    /// How many elves were added, kept or not.
+    pub fn elves(&self) -> usize {
+        self.elves
+    }
+
+    /// The kept elves, most first, as (index, total).
+    pub fn ranked(&self) -> Vec<(usize, u64)> {
+        let mut ranked: Vec<(usize, u64)> = self
+            .heap
+            .iter()
+            .map(|&Reverse((total, Reverse(i)))| (i, total))
+            .collect();
+        ranked.sort_by_key(|&(i, total)| (Reverse(total), i));
+        ranked
+    }
+}
+
+/// The `k` elves carrying the most in an inventory read from `reader`, e.g. stdin or a
+/// generated file too big to load.
+pub fn top_from_reader(reader: impl BufRead, k: usize) -> Result<TopK, String> {
+    let lines = reader
+        .lines()
+        .map(|line| line.map_err(|e| format!("Can't read the inventory: {}", e)));
+    let mut top = TopK::new(k);
+    for total in Totals::new(lines) {
+        top.push(total?);
+    }
+    Ok(top)
+}
+
+/// How many calories each elf carries, in the order the elves are listed.
+pub struct Inventory {
+    totals: Vec<u64>,
+}
+
+impl Inventory {
+    pub fn parse(lines: &[String]) -> Inventory {
+        let totals = Totals::new(lines.iter().map(Ok)).collect::<Result<_, _>>();
+        Inventory {
+            totals: totals.unwrap(),
+        }
+    }
+
+    pub fn totals(&self) -> &[u64] {
+        &self.totals
+    }
+
+    /// The `k` elves carrying the most, most first, as (index, total). Elves with the same
+    /// total each get their own place, the one listed first going first.
+    pub fn top(&self, k: usize) -> Vec<(usize, u64)> {
+        let mut top = TopK::new(k);
+        for &total in &self.totals {
+            top.push(total);
+        }
+        top.ranked()
+    }
+
+    /// Every elf carrying the most, since there can be more than one.
+    pub fn winners(&self) -> Vec<usize> {
+        let most = self.totals.iter().max();
+        (0..self.totals.len())
+            .filter(|&i| Some(&self.totals[i]) == most)
+            .collect()
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.totals.is_empty() {
+            return None;
+        }
+        Some(self.totals.iter().sum::<u64>() as f64 / self.totals.len() as f64)
+    }
 
-    let mut max_elf = 0;
-    for elf in elves.iter() {
-        if *elf > max_elf {
-            max_elf = *elf;
+    /// The middle total, or halfway between the two middle ones if there's an even number.
+    pub fn median(&self) -> Option<f64> {
+        let mut totals = self.totals.clone();
+        totals.sort_unstable();
+        let middle = totals.len() / 2;
+        match totals.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(totals[middle] as f64),
+            _ => Some((totals[middle - 1] + totals[middle]) as f64 / 2.0),
         }
     }
-    println!("{}", max_elf);
 
-    let mut second_max_elf = 0;
-    for elf in elves.iter() {
-        if *elf > second_max_elf && *elf < max_elf {
-            second_max_elf = *elf;
+    /// How many elves carry amounts in each of `buckets` equally wide ranges, from the least
+    /// anyone carries to the most.
+    pub fn histogram(&self, buckets: usize) -> Vec<(RangeInclusive<u64>, usize)> {
+        let (Some(&least), Some(&most)) = (self.totals.iter().min(), self.totals.iter().max())
+        else {
+            return vec![];
+        };
+        let width = (most - least + 1).div_ceil(buckets.max(1) as u64);
+        // rounding the width up can leave fewer buckets than asked for, but none past the most
+        let buckets = (most - least) / width + 1;
+        let mut counts = vec![0; buckets as usize];
+        for total in &self.totals {
+            counts[((total - least) / width) as usize] += 1;
         }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let from = least + i as u64 * width;
+                (from..=(from + width - 1).min(most), count)
+            })
+            .collect()
     }
+}
+
+pub fn solve_1(lines: &[String]) -> u64 {
+    Inventory::parse(lines)
+        .top(1)
+        .iter()
+        .map(|(_, total)| total)
+        .sum()
+}
+
+pub fn solve_2(lines: &[String]) -> u64 {
+    Inventory::parse(lines)
+        .top(3)
+        .iter()
+        .map(|(_, total)| total)
+        .sum()
+}
+
+struct InventoryExplorer {
+    inventory: Inventory,
+}
+
+fn number_arg(args: &[&str], default: usize) -> Result<usize, String> {
+    match args.first() {
+        Some(arg) => arg.parse().map_err(|_| format!("not a number: {}", arg)),
+        None => Ok(default),
+    }
+}
+
+fn format_stat(stat: Option<f64>) -> String {
+    stat.map_or("-".to_string(), |stat| format!("{:.1}", stat))
+}
+
+impl repl::Explorer for InventoryExplorer {
+    fn help(&self) -> Vec<&'static str> {
+        vec![
+            "top [K]        the K elves carrying the most (3 by default), e.g. `top 5`",
+            "winners        every elf tied for carrying the most",
+            "stats          how many elves there are, and the mean and median they carry",
+            "histogram [N]  how the totals are spread over N ranges (10 by default)",
+        ]
+    }
+    fn query(&self, command: &str, args: &[&str]) -> Result<String, String> {
+        let inventory = &self.inventory;
+        match command {
+            "top" => {
+                let top = inventory.top(number_arg(args, 3)?);
+                let mut lines: Vec<String> = top
+                    .iter()
+                    .map(|(i, total)| format!("elf {}: {}", i + 1, total))
+                    .collect();
+                lines.push(format!(
+                    "total: {}",
+                    top.iter().map(|(_, total)| total).sum::<u64>()
+                ));
+                Ok(lines.join("\n"))
+            }
+            "winners" => Ok(inventory
+                .winners()
+                .iter()
+                .map(|i| format!("elf {}", i + 1))
+                .collect::<Vec<_>>()
+                .join(", ")),
+            "stats" => Ok(format!(
+                "{} elves, mean {}, median {}",
+                inventory.totals().len(),
+                format_stat(inventory.mean()),
+                format_stat(inventory.median())
+            )),
+            "histogram" => {
+                let histogram = inventory.histogram(number_arg(args, 10)?);
+                let most = histogram.iter().map(|(_, count)| *count).max().unwrap_or(0);
+                let width = histogram
+                    .iter()
+                    .map(|(range, _)| format!("{:?}", range).len())
+                    .max()
+                    .unwrap_or(0);
+                Ok(histogram
+                    .iter()
+                    .map(|(range, count)| {
+                        // bars are scaled to be at most 40 wide
+                        let bar = "#".repeat((count * 40).div_ceil(most.max(1)));
+                        let range = format!("{:?}", range);
+                        format!("{:>width$}  {:<40}  {}", range, bar, count, width = width)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+            _ => Err(format!("unknown command {}", command)),
+        }
+    }
+}
+
+pub fn explorer(lines: &[String]) -> Box<dyn repl::Explorer> {
+    Box::new(InventoryExplorer {
+        inventory: Inventory::parse(lines),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{solve_2, top_from_reader, Inventory, TopK};
+    use crate::advent::lines;
+    use std::io::Cursor;
+
+    /// An inventory with one item per elf.
+    fn inventory(totals: &[u64]) -> Inventory {
+        let text: Vec<String> = totals.iter().map(u64::to_string).collect();
+        Inventory::parse(&lines(&text.join("\n\n")))
+    }
+
+    #[test]
+    fn test_ties() {
+        let lines = lines("5\n\n3\n2\n\n1\n\n5");
+        let inventory = Inventory::parse(&lines);
+        assert_eq!(inventory.totals(), [5, 5, 1, 5]);
+        assert_eq!(inventory.top(3), vec![(0, 5), (1, 5), (3, 5)]);
+        assert_eq!(inventory.winners(), vec![0, 1, 3]);
+        // the old solution skipped totals equal to the ones it already had
+        assert_eq!(solve_2(&lines), 15);
+        assert_eq!(inventory.top(10).len(), 4);
+    }
+
+    #[test]
+    fn test_stats() {
+        let example = inventory(&[6000, 4000, 11000, 24000, 10000]);
+        assert_eq!(example.mean(), Some(11000.0));
+        assert_eq!(example.median(), Some(10000.0));
+        assert_eq!(inventory(&[1, 2, 3, 10]).median(), Some(2.5));
+        assert_eq!(inventory(&[]).median(), None);
+        assert_eq!(
+            example.histogram(3),
+            vec![(4000..=10666, 3), (10667..=17333, 1), (17334..=24000, 1)]
+        );
+        assert_eq!(inventory(&[7, 7]).histogram(2), vec![(7..=7, 2)]);
+        // no buckets past the most anyone carries, even if that means fewer of them
+        assert_eq!(
+            inventory(&[0, 5, 10]).histogram(10),
+            vec![
+                (0..=1, 1),
+                (2..=3, 0),
+                (4..=5, 1),
+                (6..=7, 0),
+                (8..=9, 0),
+                (10..=10, 1)
+            ]
+        );
+        assert_eq!(
+            inventory(&[0, 5, 10]).histogram(4),
+            vec![(0..=2, 1), (3..=5, 1), (6..=8, 0), (9..=10, 1)]
+        );
+        assert!(inventory(&[]).histogram(5).is_empty());
+    }
+
+    #[test]
+    fn test_streaming() {
+        let text = "1000\n2000\n\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+        let top = top_from_reader(Cursor::new(text), 3).unwrap();
+        assert_eq!(top.elves(), 5);
+        assert_eq!(top.ranked(), vec![(3, 24000), (2, 11000), (4, 10000)]);
+        assert!(top_from_reader(Cursor::new("1000\nlots\n"), 3).is_err());
+        assert!(top_from_reader(Cursor::new("18446744073709551615\n1\n"), 3).is_err());
 
-    let mut third_max_elf = 0;
-    for elf in elves.iter() {
-        if *elf > third_max_elf && *elf < second_max_elf {
-            third_max_elf = *elf;
+        // however many elves there are, only k are kept
+        let mut top = TopK::new(2);
+        for total in (0..100000).map(|i| i % 1000) {
+            top.push(total);
         }
+        assert_eq!(top.heap.len(), 2);
+        assert_eq!(top.ranked(), vec![(999, 999), (1999, 999)]);
     }
-    println!("{}", max_elf + second_max_elf + third_max_elf)
 }
@@ -1,99 +1,480 @@
 use regex::Regex;
 
-use crate::advent;
+use crate::repl;
 
-#[derive(Debug)]
-enum ABC {
-    A,
-    B,
-    C,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
 }
-#[derive(Debug)]
-enum XYZ {
-    X,
-    Y,
-    Z,
+
+/// Points for the shape played (by its place in the game's list) and for how the round went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scoring {
+    pub shapes: Vec<i32>,
+    pub loss: i32,
+    pub draw: i32,
+    pub win: i32,
+}
+
+impl Scoring {
+    /// The puzzle's scoring: 1 point for the first shape, 2 for the second and so on,
+    /// plus 0 for a loss, 3 for a draw and 6 for a win.
+    pub fn standard(shapes: usize) -> Scoring {
+        Scoring {
+            shapes: (1..=shapes as i32).collect(),
+            loss: 0,
+            draw: 3,
+            win: 6,
+        }
+    }
+
+    fn outcome(&self, outcome: Outcome) -> i32 {
+        match outcome {
+            Outcome::Loss => self.loss,
+            Outcome::Draw => self.draw,
+            Outcome::Win => self.win,
+        }
+    }
 }
-#[derive(Debug)]
-struct Row {
-    l: ABC,
-    r: XYZ,
+
+/// A cyclic game like rock-paper-scissors: with the shapes in a circle, each one beats the
+/// half of the others that come just before it, and loses to the half that come just after.
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub shapes: Vec<String>,
+    /// What the first column of a strategy guide calls each shape
+    pub codes: Vec<char>,
+    pub scoring: Scoring,
 }
-impl Row {
-    fn parse(line: &str) -> Row {
-        let re: Regex = Regex::new(r"^([A-C]) ([X-Z])$").unwrap();
-        let captures = re.captures(&line).unwrap();
-        let abc = &captures[1];
-        let xyz = &captures[2];
-        let l = match abc {
-            "A" => ABC::A,
-            "B" => ABC::B,
-            "C" => ABC::C,
-            _ => panic!(),
-        };
-        let r = match xyz {
-            "X" => XYZ::X,
-            "Y" => XYZ::Y,
-            "Z" => XYZ::Z,
-            _ => panic!(),
+
+/// How to read the second column of a strategy guide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// The shape to play, the codes being in the same order as the game's shapes
+    Move(Vec<char>),
+    /// The outcome to aim for, the codes being for a loss, a draw and a win
+    Outcome([char; 3]),
+}
+
+impl Column {
+    /// The first part's reading: X, Y and Z are rock, paper and scissors.
+    pub fn moves() -> Column {
+        Column::Move(vec!['X', 'Y', 'Z'])
+    }
+
+    /// The second part's reading: X, Y and Z mean lose, draw and win.
+    pub fn outcomes() -> Column {
+        Column::Outcome(['X', 'Y', 'Z'])
+    }
+}
+
+impl Game {
+    pub fn new(shapes: &[&str], codes: &str) -> Result<Game, String> {
+        if shapes.len().is_multiple_of(2) {
+            return Err(format!(
+                "A game needs an odd number of shapes to be fair, not {}",
+                shapes.len()
+            ));
+        }
+        if codes.chars().count() != shapes.len() {
+            return Err(format!(
+                "{} shapes need as many codes, not {:?}",
+                shapes.len(),
+                codes
+            ));
+        }
+        Ok(Game {
+            shapes: shapes.iter().map(|shape| shape.to_string()).collect(),
+            codes: codes.chars().collect(),
+            scoring: Scoring::standard(shapes.len()),
+        })
+    }
+
+    pub fn rock_paper_scissors() -> Game {
+        Game::new(&["rock", "paper", "scissors"], "ABC").unwrap()
+    }
+
+    /// Spock smashes scissors and vaporizes rock, lizard poisons Spock and eats paper.
+    /// Guides for it use A to E for the other player, and V to Z for moves.
+    pub fn rock_paper_scissors_lizard_spock() -> Game {
+        Game::new(&["rock", "Spock", "paper", "lizard", "scissors"], "ABCDE").unwrap()
+    }
+
+    pub fn with_scoring(self, scoring: Scoring) -> Result<Game, String> {
+        if scoring.shapes.len() != self.shapes.len() {
+            return Err(format!(
+                "The scoring has points for {} shapes, but the game has {}",
+                scoring.shapes.len(),
+                self.shapes.len()
+            ));
+        }
+        Ok(Game { scoring, ..self })
+    }
+
+    /// How playing `mine` against `theirs` goes, both being places in the list of shapes.
+    pub fn outcome(&self, mine: usize, theirs: usize) -> Outcome {
+        let n = self.shapes.len();
+        match (mine + n - theirs) % n {
+            0 => Outcome::Draw,
+            ahead if ahead <= n / 2 => Outcome::Win,
+            _ => Outcome::Loss,
+        }
+    }
+
+    /// A shape that gets `outcome` against `theirs`. When several would, it's the closest
+    /// one around the circle.
+    pub fn shape_for(&self, theirs: usize, outcome: Outcome) -> usize {
+        let n = self.shapes.len();
+        match outcome {
+            Outcome::Draw => theirs,
+            Outcome::Win => (theirs + 1) % n,
+            Outcome::Loss => (theirs + n - 1) % n,
+        }
+    }
+
+    pub fn score(&self, mine: usize, theirs: usize) -> i32 {
+        self.scoring.shapes[mine] + self.scoring.outcome(self.outcome(mine, theirs))
+    }
+
+    /// The shape to play in a round, going by the guide's second column read as `column`.
+    pub fn my_shape(&self, row: &Row, column: &Column) -> Result<usize, String> {
+        let position = |codes: &[char]| {
+            codes
+                .iter()
+                .position(|&code| code == row.code)
+                .ok_or(format!("Don't know what {} means", row.code))
         };
-        Row { l, r }
-    }
-    fn score_1(&self) -> i32 {
-        match &self.r {
-            XYZ::X => {
-                1 + match &self.l {
-                    ABC::A => 3,
-                    ABC::B => 0,
-                    ABC::C => 6,
-                }
+        match column {
+            Column::Move(codes) if codes.len() != self.shapes.len() => Err(format!(
+                "{} shapes need as many move codes, not {:?}",
+                self.shapes.len(),
+                codes
+            )),
+            Column::Move(codes) => position(codes),
+            Column::Outcome(codes) => {
+                let outcome = [Outcome::Loss, Outcome::Draw, Outcome::Win][position(codes)?];
+                Ok(self.shape_for(row.theirs, outcome))
             }
-            XYZ::Y => {
-                2 + match &self.l {
-                    ABC::A => 6,
-                    ABC::B => 3,
-                    ABC::C => 0,
-                }
-            }
-            XYZ::Z => {
-                3 + match &self.l {
-                    ABC::A => 0,
-                    ABC::B => 6,
-                    ABC::C => 3,
-                }
+        }
+    }
+
+    /// The total score of following a whole guide.
+    pub fn score_guide(&self, rows: &[Row], column: &Column) -> Result<i32, String> {
+        rows.iter().try_fold(0, |total, row| {
+            Ok(total + self.score(self.my_shape(row, column)?, row.theirs))
+        })
+    }
+
+    /// How each round of a guide goes.
+    pub fn rounds(&self, rows: &[Row], column: &Column) -> Result<Vec<Round>, String> {
+        rows.iter()
+            .map(|row| {
+                let mine = self.my_shape(row, column)?;
+                Ok(Round {
+                    theirs: row.theirs,
+                    mine,
+                    outcome: self.outcome(mine, row.theirs),
+                    score: self.score(mine, row.theirs),
+                })
+            })
+            .collect()
+    }
+
+    /// Every way of reading `codes` as moves, one code per shape, with the score following
+    /// the guide that way gets. Best first, and in the order of `codes` otherwise.
+    pub fn decodings(&self, rows: &[Row], codes: &[char]) -> Result<Vec<(Column, i32)>, String> {
+        let mut decodings = permutations(codes)
+            .into_iter()
+            .map(|codes| {
+                let column = Column::Move(codes);
+                let score = self.score_guide(rows, &column)?;
+                Ok((column, score))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        decodings.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        Ok(decodings)
+    }
+
+    /// A table of how each round goes, with a running total.
+    pub fn render_rounds(&self, rounds: &[Round]) -> String {
+        let width = self.shapes.iter().map(|s| s.len()).max().unwrap_or(0);
+        let mut total = 0;
+        let mut lines = vec![format!(
+            "round  {:<width$}  {:<width$}  outcome  score  total",
+            "them",
+            "me",
+            width = width
+        )];
+        for (i, round) in rounds.iter().enumerate() {
+            total += round.score;
+            lines.push(format!(
+                "{:>5}  {:<width$}  {:<width$}  {:<7}  {:>5}  {:>5}",
+                i + 1,
+                self.shapes[round.theirs],
+                self.shapes[round.mine],
+                format!("{:?}", round.outcome).to_lowercase(),
+                round.score,
+                total,
+                width = width
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// How one round of a guide went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub theirs: usize,
+    pub mine: usize,
+    pub outcome: Outcome,
+    pub score: i32,
+}
+
+/// Every ordering of `items`, starting with the one they're in.
+fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        return vec![vec![]];
+    }
+    (0..items.len())
+        .flat_map(|i| {
+            let mut rest = items.to_vec();
+            let first = rest.remove(i);
+            permutations(&rest).into_iter().map(move |mut ordering| {
+                ordering.insert(0, first.clone());
+                ordering
+            })
+        })
+        .collect()
+}
+
+/// A round of a strategy guide: what the other player plays, and the code for what to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub theirs: usize,
+    pub code: char,
+}
+
+impl Row {
+    pub fn parse(game: &Game, line: &str) -> Result<Row, String> {
+        let re: Regex = Regex::new(r"^(\S) (\S)$").unwrap();
+        let captures = re
+            .captures(line)
+            .ok_or(format!("Not a round of a strategy guide: {}", line))?;
+        let their_code = captures[1].chars().next().unwrap();
+        let theirs = game
+            .codes
+            .iter()
+            .position(|&code| code == their_code)
+            .ok_or(format!("No shape is called {}", their_code))?;
+        Ok(Row {
+            theirs,
+            code: captures[2].chars().next().unwrap(),
+        })
+    }
+}
+
+pub fn parse_rows(game: &Game, lines: &[String]) -> Result<Vec<Row>, String> {
+    lines.iter().map(|line| Row::parse(game, line)).collect()
+}
+
+pub fn solve_1(lines: &[String]) -> i32 {
+    let game = Game::rock_paper_scissors();
+    let rows = parse_rows(&game, lines).unwrap();
+    game.score_guide(&rows, &Column::moves()).unwrap()
+}
+
+pub fn solve_2(lines: &[String]) -> i32 {
+    let game = Game::rock_paper_scissors();
+    let rows = parse_rows(&game, lines).unwrap();
+    game.score_guide(&rows, &Column::outcomes()).unwrap()
+}
+
+/// Looks at the real guide with the rock-paper-scissors rules.
+struct GuideExplorer {
+    game: Game,
+    rows: Result<Vec<Row>, String>,
+}
+
+impl GuideExplorer {
+    /// A column from e.g. `YXZ` (the codes for rock, paper and scissors) or `outcomes`.
+    fn column(&self, arg: Option<&&str>) -> Result<Column, String> {
+        match arg {
+            None => Ok(Column::moves()),
+            Some(&"outcomes") => Ok(Column::outcomes()),
+            Some(codes) => Ok(Column::Move(codes.chars().collect())),
+        }
+    }
+
+    fn describe(&self, column: &Column) -> String {
+        match column {
+            Column::Move(codes) => codes
+                .iter()
+                .zip(&self.game.shapes)
+                .map(|(code, shape)| format!("{}={}", code, shape))
+                .collect::<Vec<_>>()
+                .join(" "),
+            Column::Outcome(codes) => {
+                format!("{}=lose {}=draw {}=win", codes[0], codes[1], codes[2])
             }
         }
     }
-    fn score_2(&self) -> i32 {
-        match &self.l {
-            ABC::A => match &self.r {
-                XYZ::X => 0 + 3,
-                XYZ::Y => 3 + 1,
-                XYZ::Z => 6 + 2,
-            },
-            ABC::B => match &self.r {
-                XYZ::X => 0 + 1,
-                XYZ::Y => 3 + 2,
-                XYZ::Z => 6 + 3,
-            },
-            ABC::C => match &self.r {
-                XYZ::X => 0 + 2,
-                XYZ::Y => 3 + 3,
-                XYZ::Z => 6 + 1,
-            },
+}
+
+impl repl::Explorer for GuideExplorer {
+    fn help(&self) -> Vec<&'static str> {
+        vec![
+            "decodings        the score of every way of reading X, Y and Z as moves, best first",
+            "best             the way of reading X, Y and Z as moves that scores the most",
+            "rounds [CODES]   how each round goes, e.g. `rounds ZXY` for Z=rock X=paper Y=scissors,",
+            "                 or `rounds outcomes` for part 2's reading",
+        ]
+    }
+    fn query(&self, command: &str, args: &[&str]) -> Result<String, String> {
+        let rows = self.rows.as_ref().map_err(Clone::clone)?;
+        let decodings = || self.game.decodings(rows, &['X', 'Y', 'Z']);
+        match command {
+            "decodings" => Ok(decodings()?
+                .iter()
+                .map(|(column, score)| format!("{}  {}", self.describe(column), score))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            "best" => {
+                let decodings = decodings()?;
+                let (column, score) = &decodings[0];
+                Ok(format!("{} scores {}", self.describe(column), score))
+            }
+            "rounds" => {
+                let column = self.column(args.first())?;
+                let rounds = self.game.rounds(rows, &column)?;
+                Ok(self.game.render_rounds(&rounds))
+            }
+            _ => Err(format!("unknown command {}", command)),
         }
     }
 }
 
-pub fn solve() {
-    let reader = advent::read_input(2);
-    let rows: Vec<Row> = reader.iter().map(|line| Row::parse(line)).collect();
+pub fn explorer(lines: &[String]) -> Box<dyn repl::Explorer> {
+    let game = Game::rock_paper_scissors();
+    let rows = parse_rows(&game, lines);
+    Box::new(GuideExplorer { game, rows })
+}
 
-    // Part 1
-    let sum_1 = rows.iter().fold(0, |acc, row| acc + row.score_1());
-    println!("{}", sum_1);
+#[cfg(test)]
+mod test {
+    use super::{parse_rows, permutations, Column, Game, Outcome, Row, Scoring};
+    use crate::advent::lines;
 
-    // Part 2
-    let sum_2 = rows.iter().fold(0, |acc, row| acc + row.score_2());
-    println!("{}", sum_2);
+    #[test]
+    fn test_rock_paper_scissors() {
+        let game = Game::rock_paper_scissors();
+        let rows = parse_rows(&game, &lines("A Y\nB X\nC Z")).unwrap();
+        assert_eq!(game.score_guide(&rows, &Column::moves()), Ok(15));
+        assert_eq!(game.score_guide(&rows, &Column::outcomes()), Ok(12));
+        // every pairing, against the puzzle's rules
+        let beats = [(0, 2), (1, 0), (2, 1)];
+        for mine in 0..3 {
+            for theirs in 0..3 {
+                let expected = if mine == theirs {
+                    Outcome::Draw
+                } else if beats.contains(&(mine, theirs)) {
+                    Outcome::Win
+                } else {
+                    Outcome::Loss
+                };
+                assert_eq!(game.outcome(mine, theirs), expected);
+            }
+        }
+        assert!(Row::parse(&game, "D X").is_err());
+        assert!(game
+            .score_guide(&rows, &Column::Move(vec!['X', 'Y']))
+            .is_err());
+    }
+
+    #[test]
+    fn test_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let shape = |name| game.shapes.iter().position(|s| s == name).unwrap();
+        let wins = [
+            ("scissors", "paper"),
+            ("paper", "rock"),
+            ("rock", "lizard"),
+            ("lizard", "Spock"),
+            ("Spock", "scissors"),
+            ("scissors", "lizard"),
+            ("lizard", "paper"),
+            ("paper", "Spock"),
+            ("Spock", "rock"),
+            ("rock", "scissors"),
+        ];
+        for (winner, loser) in wins {
+            assert_eq!(game.outcome(shape(winner), shape(loser)), Outcome::Win);
+            assert_eq!(game.outcome(shape(loser), shape(winner)), Outcome::Loss);
+        }
+        for theirs in 0..5 {
+            for outcome in [Outcome::Loss, Outcome::Draw, Outcome::Win] {
+                assert_eq!(
+                    game.outcome(game.shape_for(theirs, outcome), theirs),
+                    outcome
+                );
+            }
+        }
+
+        let game = game
+            .with_scoring(Scoring {
+                shapes: vec![1, 1, 1, 1, 1],
+                loss: -1,
+                draw: 0,
+                win: 1,
+            })
+            .unwrap();
+        let rows = parse_rows(&game, &lines("A W\nE V\nD Z")).unwrap();
+        let moves = Column::Move(vec!['V', 'W', 'X', 'Y', 'Z']);
+        // Spock beats rock, rock beats scissors, scissors beat lizard
+        assert_eq!(game.score_guide(&rows, &moves), Ok(6));
+        assert_eq!(
+            game.score_guide(&rows, &Column::Outcome(['V', 'W', 'Z'])),
+            Ok(3)
+        );
+        assert!(Game::new(&["rock", "paper"], "AB").is_err());
+        assert!(Game::rock_paper_scissors()
+            .with_scoring(Scoring::standard(5))
+            .is_err());
+    }
+
+    #[test]
+    fn test_decodings() {
+        assert_eq!(
+            permutations(&[1, 2, 3]),
+            vec![
+                vec![1, 2, 3],
+                vec![1, 3, 2],
+                vec![2, 1, 3],
+                vec![2, 3, 1],
+                vec![3, 1, 2],
+                vec![3, 2, 1]
+            ]
+        );
+        let game = Game::rock_paper_scissors();
+        let rows = parse_rows(&game, &lines("A Y\nB X\nC Z")).unwrap();
+        let decodings = game.decodings(&rows, &['X', 'Y', 'Z']).unwrap();
+        assert_eq!(decodings.len(), 6);
+        // Z=rock Y=paper X=scissors wins every round
+        assert_eq!(decodings[0], (Column::Move(vec!['Z', 'Y', 'X']), 8 + 9 + 7));
+        assert!(decodings.contains(&(Column::moves(), 15)));
+
+        let rounds = game.rounds(&rows, &Column::outcomes()).unwrap();
+        assert_eq!(
+            game.render_rounds(&rounds),
+            [
+                "round  them      me        outcome  score  total",
+                "    1  rock      rock      draw         4      4",
+                "    2  paper     rock      loss         1      5",
+                "    3  scissors  rock      win          7     12",
+            ]
+            .join("\n")
+        );
+    }
 }
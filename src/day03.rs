@@ -1,47 +1,240 @@
-use substring::Substring;
-
-use crate::advent;
-
-fn priority(c: char) -> u32 {
-    if c.is_uppercase() {
-        (c as u32) - 38
-    } else {
-        (c as u32) - 96
-    }
-}
-
-pub fn solve() {
-    // part 1
-    let reader = advent::read_input(3);
-    let mut sum = 0;
-    let mut lines = 0;
-    for line in reader.iter() {
-        let left = line.substring(0, line.len() / 2);
-        let right = line.substring(line.len() / 2, line.len());
-        for c in left.chars() {
-            if right.contains(c) {
-                sum += priority(c);
-                break;
+use crate::repl;
+
+/// A set of items, kept as one bit per priority: a to z are 1 to 26, A to Z are 27 to 52.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Items(u64);
+
+fn priority(item: char) -> Result<u32, String> {
+    match item {
+        'a'..='z' => Ok(item as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Ok(item as u32 - 'A' as u32 + 27),
+        _ => Err(format!("{:?} isn't an item", item)),
+    }
+}
+
+fn item(priority: u32) -> char {
+    match priority {
+        1..=26 => (b'a' + (priority - 1) as u8) as char,
+        _ => (b'A' + (priority - 27) as u8) as char,
+    }
+}
+
+impl Items {
+    pub fn parse(items: &str) -> Result<Items, String> {
+        let priorities = items.chars().map(priority).collect::<Result<Vec<_>, _>>()?;
+        Ok(Items::from_priorities(&priorities))
+    }
+
+    fn from_priorities(priorities: &[u32]) -> Items {
+        Items(priorities.iter().fold(0, |set, p| set | 1 << p))
+    }
+
+    /// Every item there is.
+    pub fn all() -> Items {
+        Items(((1 << 52) - 1) << 1)
+    }
+
+    pub fn intersection(self, other: Items) -> Items {
+        Items(self.0 & other.0)
+    }
+
+    pub fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The priorities of the items in the set, lowest first.
+    pub fn priorities(self) -> impl Iterator<Item = u32> {
+        (1..=52).filter(move |p| self.0 & 1 << p != 0)
+    }
+
+    pub fn items(self) -> String {
+        self.priorities().map(item).collect()
+    }
+
+    pub fn total_priority(self) -> u32 {
+        self.priorities().sum()
+    }
+}
+
+/// The items in both compartments of a rucksack, the first half of its items being in one
+/// and the second half in the other.
+pub fn shared_by_compartments(rucksack: &str) -> Result<Items, String> {
+    let priorities = rucksack
+        .chars()
+        .map(priority)
+        .collect::<Result<Vec<_>, _>>()?;
+    if !priorities.len().is_multiple_of(2) {
+        return Err(format!(
+            "{} has an odd number of items, so it can't be split in half",
+            rucksack
+        ));
+    }
+    let (left, right) = priorities.split_at(priorities.len() / 2);
+    Ok(Items::from_priorities(left).intersection(Items::from_priorities(right)))
+}
+
+/// The items every rucksack in a group has, for each group of `size` rucksacks in a row.
+pub fn shared_by_groups(rucksacks: &[String], size: usize) -> Result<Vec<Items>, String> {
+    if size == 0 {
+        return Err("Groups need at least one rucksack".to_string());
+    }
+    if !rucksacks.len().is_multiple_of(size) {
+        return Err(format!(
+            "{} rucksacks don't make groups of {}, the last group only has {}",
+            rucksacks.len(),
+            size,
+            rucksacks.len() % size
+        ));
+    }
+    rucksacks
+        .chunks(size)
+        .map(|group| {
+            group.iter().try_fold(Items::all(), |shared, rucksack| {
+                Ok(shared.intersection(Items::parse(rucksack)?))
+            })
+        })
+        .collect()
+}
+
+pub fn solve_1(lines: &[String]) -> u32 {
+    lines
+        .iter()
+        .map(|line| shared_by_compartments(line).unwrap().total_priority())
+        .sum()
+}
+
+pub fn solve_2(lines: &[String]) -> u32 {
+    shared_by_groups(lines, 3)
+        .unwrap()
+        .iter()
+        .map(|items| items.total_priority())
+        .sum()
+}
+
+struct RucksackExplorer {
+    rucksacks: Vec<String>,
+}
+
+/// One line per rucksack or group, with what they share and its priority.
+fn describe(shared: &[Items]) -> String {
+    let mut lines: Vec<String> = shared
+        .iter()
+        .enumerate()
+        .map(|(i, items)| {
+            format!(
+                "{:>4}: {} ({})",
+                i + 1,
+                if items.is_empty() {
+                    "-".to_string()
+                } else {
+                    items.items()
+                },
+                items.total_priority()
+            )
+        })
+        .collect();
+    lines.push(format!(
+        "total: {}",
+        shared
+            .iter()
+            .map(|items| items.total_priority())
+            .sum::<u32>()
+    ));
+    lines.join("\n")
+}
+
+impl repl::Explorer for RucksackExplorer {
+    fn help(&self) -> Vec<&'static str> {
+        vec![
+            "compartments  the items both compartments of each rucksack have",
+            "groups N      the items each group of N rucksacks all have, e.g. `groups 3`",
+        ]
+    }
+    fn query(&self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "compartments" => {
+                let shared = self
+                    .rucksacks
+                    .iter()
+                    .map(|rucksack| shared_by_compartments(rucksack))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(describe(&shared))
             }
-        }
-        lines += 1;
-    }
-    println!("{}", sum);
-
-    // part 2
-    let file = advent::read_input(3);
-    let mut reader = file.iter();
-    let mut sum = 0;
-    for _i in (0..lines).step_by(3) {
-        let a = reader.next().unwrap();
-        let b = reader.next().unwrap();
-        let c = reader.next().unwrap();
-        for chr in a.chars() {
-            if b.contains(chr) && c.contains(chr) {
-                sum += priority(chr);
-                break;
+            "groups" => {
+                let size = args.first().ok_or("missing group size")?;
+                let size = size
+                    .parse()
+                    .map_err(|_| format!("not a group size: {}", size))?;
+                Ok(describe(&shared_by_groups(&self.rucksacks, size)?))
             }
+            _ => Err(format!("unknown command {}", command)),
         }
     }
-    println!("{}", sum);
+}
+
+pub fn explorer(lines: &[String]) -> Box<dyn repl::Explorer> {
+    Box::new(RucksackExplorer {
+        rucksacks: lines.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{shared_by_compartments, shared_by_groups, Items};
+    use crate::advent::lines;
+
+    #[test]
+    fn test_items() {
+        let items = Items::parse("vJrwpWtwJgWr").unwrap();
+        assert_eq!(items.items(), "gprtvwJW");
+        assert_eq!(items.len(), 8);
+        assert_eq!(Items::all().len(), 52);
+        assert_eq!(
+            Items::parse("azAZ")
+                .unwrap()
+                .priorities()
+                .collect::<Vec<_>>(),
+            vec![1, 26, 27, 52]
+        );
+        assert!(Items::parse("ab1").is_err());
+        assert_eq!(
+            shared_by_compartments("vJrwpWtwJgWrhcsFMMfFFhFp")
+                .unwrap()
+                .items(),
+            "p"
+        );
+        // everything they share, not just the first
+        assert_eq!(shared_by_compartments("abcCba").unwrap().items(), "ab");
+        assert!(shared_by_compartments("abc").is_err());
+        // anything that isn't an item is an error, wherever it is
+        assert_eq!(
+            shared_by_compartments("aé").unwrap_err(),
+            "'é' isn't an item"
+        );
+        assert!(shared_by_compartments("éa").is_err());
+    }
+
+    #[test]
+    fn test_groups() {
+        let lines = lines("abcd\nbcde\ncdef\nxyzc\ncxyz\nzyxc");
+        let items = |groups: Vec<Items>| groups.iter().map(|i| i.items()).collect::<Vec<_>>();
+        assert_eq!(
+            items(shared_by_groups(&lines, 3).unwrap()),
+            vec!["cd", "cxyz"]
+        );
+        assert_eq!(
+            items(shared_by_groups(&lines, 2).unwrap()),
+            vec!["bcd", "c", "cxyz"]
+        );
+        assert_eq!(items(shared_by_groups(&lines, 6).unwrap()), vec!["c"]);
+        assert_eq!(
+            shared_by_groups(&lines, 4).unwrap_err(),
+            "6 rucksacks don't make groups of 4, the last group only has 2"
+        );
+        assert!(shared_by_groups(&lines, 0).is_err());
+    }
 }
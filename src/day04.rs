@@ -1,45 +1,389 @@
+use std::collections::BTreeSet;
+
 use regex::{Captures, Regex};
 
-use crate::advent;
+use crate::intervals::IntervalSet;
+use crate::repl;
+
+/// How two ranges of sections lie relative to each other, as in Allen's interval algebra.
+/// Sections are whole numbers, so ranges "meet" when one ends right before the other starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relation {
+    Before,
+    Meets,
+    Overlaps,
+    Starts,
+    During,
+    Finishes,
+    Equals,
+    FinishedBy,
+    Contains,
+    StartedBy,
+    OverlappedBy,
+    MetBy,
+    After,
+}
+
+impl Relation {
+    /// How `a` lies relative to `b`, worked out from what they share and what's left of each.
+    pub fn classify(a: &IntervalSet<i32>, b: &IntervalSet<i32>) -> Relation {
+        let shared = a.intersection(b);
+        if shared.is_empty() {
+            // touching ranges merge, so two that meet make a single range
+            let meets = !a.is_empty() && !b.is_empty() && a.union(b).iter().count() == 1;
+            return match (a.min() < b.min(), meets) {
+                (true, true) => Relation::Meets,
+                (true, false) => Relation::Before,
+                (false, true) => Relation::MetBy,
+                (false, false) => Relation::After,
+            };
+        }
+        let (only_a, only_b) = (a.difference(b), b.difference(a));
+        let before = |rest: &IntervalSet<i32>| rest.min() < shared.min();
+        let after = |rest: &IntervalSet<i32>| rest.max() > shared.max();
+        match (only_a.is_empty(), only_b.is_empty()) {
+            (true, true) => Relation::Equals,
+            (true, false) => match (before(&only_b), after(&only_b)) {
+                (true, true) => Relation::During,
+                (true, false) => Relation::Finishes,
+                _ => Relation::Starts,
+            },
+            (false, true) => match (before(&only_a), after(&only_a)) {
+                (true, true) => Relation::Contains,
+                (true, false) => Relation::FinishedBy,
+                _ => Relation::StartedBy,
+            },
+            (false, false) if before(&only_a) => Relation::Overlaps,
+            (false, false) => Relation::OverlappedBy,
+        }
+    }
+
+    /// How the second range lies relative to the first.
+    pub fn inverse(self) -> Relation {
+        match self {
+            Relation::Before => Relation::After,
+            Relation::Meets => Relation::MetBy,
+            Relation::Overlaps => Relation::OverlappedBy,
+            Relation::Starts => Relation::StartedBy,
+            Relation::During => Relation::Contains,
+            Relation::Finishes => Relation::FinishedBy,
+            Relation::Equals => Relation::Equals,
+            Relation::FinishedBy => Relation::Finishes,
+            Relation::Contains => Relation::During,
+            Relation::StartedBy => Relation::Starts,
+            Relation::OverlappedBy => Relation::Overlaps,
+            Relation::MetBy => Relation::Meets,
+            Relation::After => Relation::Before,
+        }
+    }
+
+    /// Whether one of the ranges has every section the other does.
+    pub fn is_nested(self) -> bool {
+        !self.is_disjoint() && !matches!(self, Relation::Overlaps | Relation::OverlappedBy)
+    }
+
+    pub fn is_disjoint(self) -> bool {
+        matches!(
+            self,
+            Relation::Before | Relation::Meets | Relation::MetBy | Relation::After
+        )
+    }
+
+    /// The usual short name, with inverses marked by an `i`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Relation::Before => "b",
+            Relation::Meets => "m",
+            Relation::Overlaps => "o",
+            Relation::Starts => "s",
+            Relation::During => "d",
+            Relation::Finishes => "f",
+            Relation::Equals => "=",
+            Relation::FinishedBy => "fi",
+            Relation::Contains => "di",
+            Relation::StartedBy => "si",
+            Relation::OverlappedBy => "oi",
+            Relation::MetBy => "mi",
+            Relation::After => "bi",
+        }
+    }
+}
 
 #[derive(Debug)]
 struct Row {
-    a: i32,
-    b: i32,
-    x: i32,
-    y: i32,
+    first: IntervalSet<i32>,
+    second: IntervalSet<i32>,
 }
 
 impl Row {
     fn new(captures: Captures) -> Row {
+        let number = |i: usize| str::parse::<i32>(&captures[i]).unwrap();
         Row {
-            a: str::parse(&captures[1]).unwrap(),
-            b: str::parse(&captures[2]).unwrap(),
-            x: str::parse(&captures[3]).unwrap(),
-            y: str::parse(&captures[4]).unwrap(),
+            first: IntervalSet::from(number(1)..=number(2)),
+            second: IntervalSet::from(number(3)..=number(4)),
         }
     }
+    fn relation(&self) -> Relation {
+        Relation::classify(&self.first, &self.second)
+    }
     fn is_nested(&self) -> bool {
-        return (self.a <= self.x && self.y <= self.b) || (self.x <= self.a && self.b <= self.y);
+        self.first.difference(&self.second).is_empty()
+            || self.second.difference(&self.first).is_empty()
     }
     fn is_overlapping(&self) -> bool {
-        return (self.a <= self.x && self.x <= self.b) || (self.x <= self.a && self.a <= self.y);
+        !self.first.intersection(&self.second).is_empty()
     }
 }
 
-pub fn solve() {
-    let reader = advent::read_input(4);
+fn parse_rows(lines: &[String]) -> Vec<Row> {
     let re: Regex = Regex::new(r"^([0-9]+)-([0-9]+),([0-9]+)-([0-9]+)$").unwrap();
-    let rows: Vec<Row> = reader
+    lines
         .iter()
-        .map(|line| Row::new(re.captures(&line).unwrap()))
-        .collect();
-    let nested = rows
+        .map(|line| Row::new(re.captures(line).unwrap()))
+        .collect()
+}
+
+/// One elf's sections: which line of the file it's on, and whether it's the first or second
+/// elf there (0 or 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub line: usize,
+    pub elf: usize,
+    pub sections: IntervalSet<i32>,
+}
+
+fn assignments(rows: &[Row]) -> Vec<Assignment> {
+    rows.iter()
+        .enumerate()
+        .flat_map(|(line, row)| {
+            [&row.first, &row.second]
+                .into_iter()
+                .enumerate()
+                .map(move |(elf, sections)| Assignment {
+                    line,
+                    elf,
+                    sections: sections.clone(),
+                })
+        })
+        .collect()
+}
+
+/// Every pair of assignments anywhere in the file that share a section, found by sweeping
+/// over the sections from low to high, keeping track of the assignments that are still open.
+/// Pairs are in the order their second assignment starts.
+pub fn overlapping_pairs(assignments: &[Assignment]) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..assignments.len()).collect();
+    order.sort_by_key(|&i| (assignments[i].sections.min(), i));
+    // (end, index) of the assignments that started already and might not have ended yet
+    let mut open: BTreeSet<(i32, usize)> = BTreeSet::new();
+    let mut pairs = vec![];
+    for i in order {
+        let sections = &assignments[i].sections;
+        let (Some(start), Some(end)) = (sections.min(), sections.max()) else {
+            continue;
+        };
+        while open.first().is_some_and(|&(open_end, _)| open_end < start) {
+            open.pop_first();
+        }
+        pairs.extend(open.iter().map(|&(_, j)| (j, i)));
+        open.insert((end, i));
+    }
+    pairs
+}
+
+pub fn solve_1(lines: &[String]) -> i32 {
+    parse_rows(lines)
         .iter()
-        .fold(0, |acc, row| acc + (row.is_nested() as i32));
-    println!("{}", nested);
-    let overlapping = rows
+        .fold(0, |acc, row| acc + (row.is_nested() as i32))
+}
+
+pub fn solve_2(lines: &[String]) -> i32 {
+    parse_rows(lines)
         .iter()
-        .fold(0, |acc, row| acc + (row.is_overlapping() as i32));
-    println!("{}", overlapping);
+        .fold(0, |acc, row| acc + (row.is_overlapping() as i32))
+}
+
+struct AssignmentExplorer {
+    rows: Vec<Row>,
+}
+
+/// Sections the way the input writes them, e.g. `2-4`.
+fn format_sections(sections: &IntervalSet<i32>) -> String {
+    match (sections.min(), sections.max()) {
+        (Some(start), Some(end)) => format!("{}-{}", start, end),
+        _ => "none".to_string(),
+    }
+}
+
+fn describe(assignment: &Assignment) -> String {
+    format!(
+        "line {} elf {} ({})",
+        assignment.line + 1,
+        assignment.elf + 1,
+        format_sections(&assignment.sections)
+    )
+}
+
+impl repl::Explorer for AssignmentExplorer {
+    fn help(&self) -> Vec<&'static str> {
+        vec![
+            "relations   how the two assignments on each line lie relative to each other",
+            "counts      how many lines there are of each relation",
+            "overlaps    every pair of assignments in the whole file that share a section",
+            "matrix      the relation between every pair of assignments (small files only)",
+        ]
+    }
+    fn query(&self, command: &str, _args: &[&str]) -> Result<String, String> {
+        match command {
+            "relations" => Ok(self
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    format!(
+                        "{:>4}: {} {:?} {}",
+                        i + 1,
+                        format_sections(&row.first),
+                        row.relation(),
+                        format_sections(&row.second)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")),
+            "counts" => {
+                let mut relations: Vec<Relation> = self.rows.iter().map(Row::relation).collect();
+                relations.sort();
+                let mut counts: Vec<(Relation, usize)> = vec![];
+                for relation in relations {
+                    match counts.last_mut() {
+                        Some((last, count)) if *last == relation => *count += 1,
+                        _ => counts.push((relation, 1)),
+                    }
+                }
+                Ok(counts
+                    .iter()
+                    .map(|(relation, count)| format!("{:?}: {}", relation, count))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+            "overlaps" => {
+                let assignments = assignments(&self.rows);
+                let pairs = overlapping_pairs(&assignments);
+                let mut lines: Vec<String> = pairs
+                    .iter()
+                    .map(|&(a, b)| {
+                        let relation =
+                            Relation::classify(&assignments[a].sections, &assignments[b].sections);
+                        format!(
+                            "{} {:?} {}",
+                            describe(&assignments[a]),
+                            relation,
+                            describe(&assignments[b])
+                        )
+                    })
+                    .collect();
+                lines.push(format!("{} overlapping pair(s)", pairs.len()));
+                Ok(lines.join("\n"))
+            }
+            "matrix" => {
+                let assignments = assignments(&self.rows);
+                if assignments.len() > 50 {
+                    return Err(format!(
+                        "{} assignments are too many to show, try `overlaps`",
+                        assignments.len()
+                    ));
+                }
+                let mut lines = vec![format!(
+                    "     {}",
+                    (1..=assignments.len())
+                        .map(|i| format!("{:>3}", i))
+                        .collect::<String>()
+                )];
+                for (i, a) in assignments.iter().enumerate() {
+                    let cells: String = assignments
+                        .iter()
+                        .map(|b| {
+                            format!(
+                                "{:>3}",
+                                Relation::classify(&a.sections, &b.sections).symbol()
+                            )
+                        })
+                        .collect();
+                    lines.push(format!("{:>3}  {}", i + 1, cells));
+                }
+                Ok(lines.join("\n"))
+            }
+            _ => Err(format!("unknown command {}", command)),
+        }
+    }
+}
+
+pub fn explorer(lines: &[String]) -> Box<dyn repl::Explorer> {
+    Box::new(AssignmentExplorer {
+        rows: parse_rows(lines),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{assignments, overlapping_pairs, parse_rows, Relation};
+    use crate::advent::read_example;
+    use crate::intervals::IntervalSet;
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            (1..=2, 4..=5, Relation::Before),
+            (1..=3, 4..=5, Relation::Meets),
+            (1..=4, 4..=5, Relation::Overlaps),
+            (4..=5, 4..=8, Relation::Starts),
+            (5..=6, 4..=8, Relation::During),
+            (6..=8, 4..=8, Relation::Finishes),
+            (4..=8, 4..=8, Relation::Equals),
+        ];
+        for (a, b, relation) in cases {
+            let (a, b) = (IntervalSet::from(a), IntervalSet::from(b));
+            assert_eq!(Relation::classify(&a, &b), relation);
+            assert_eq!(Relation::classify(&b, &a), relation.inverse());
+        }
+        let single = IntervalSet::from(6..=6);
+        assert_eq!(Relation::classify(&single, &single), Relation::Equals);
+
+        // nested, overlapping and meeting agree with comparing the ends directly
+        for (s1, e1, s2, e2) in (0..6).flat_map(|s1| {
+            (s1..6).flat_map(move |e1| {
+                (0..6).flat_map(move |s2| (s2..6).map(move |e2| (s1, e1, s2, e2)))
+            })
+        }) {
+            let (a, b) = (IntervalSet::from(s1..=e1), IntervalSet::from(s2..=e2));
+            let relation = Relation::classify(&a, &b);
+            let nested = (s1 <= s2 && e2 <= e1) || (s2 <= s1 && e1 <= e2);
+            assert_eq!(relation.is_nested(), nested, "{:?}", relation);
+            assert_eq!(relation.is_disjoint(), e1 < s2 || e2 < s1);
+            assert_eq!(relation == Relation::Meets, e1 + 1 == s2);
+            assert_eq!(Relation::classify(&b, &a), relation.inverse());
+        }
+    }
+
+    #[test]
+    fn test_overlapping_pairs() {
+        let assignments = assignments(&parse_rows(&read_example(4)));
+        let pairs = overlapping_pairs(&assignments);
+        // brute force says the same
+        let mut expected = vec![];
+        for i in 0..assignments.len() {
+            for j in i + 1..assignments.len() {
+                if !Relation::classify(&assignments[i].sections, &assignments[j].sections)
+                    .is_disjoint()
+                {
+                    expected.push((i, j));
+                }
+            }
+        }
+        let mut sorted: Vec<(usize, usize)> =
+            pairs.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+        sorted.sort();
+        assert_eq!(sorted, expected);
+        assert_eq!(pairs.len(), 49);
+    }
 }
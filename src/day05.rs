@@ -2,10 +2,10 @@ use std::fmt::Debug;
 
 use regex::Regex;
 
-use crate::advent;
+use crate::repl;
 
-#[derive(Debug)]
-struct Yard {
+#[derive(Debug, Clone)]
+pub struct Yard {
     stacks: Vec<Stack>,
 }
 
@@ -13,31 +13,72 @@ impl Yard {
     fn new() -> Yard {
         Yard { stacks: vec![] }
     }
-    fn move_crates(&mut self, num: i32, from: usize, to: usize) {
-        for _ in 0..num {
-            let carte = self.stacks[from].crates.pop().unwrap();
-            self.stacks[to].crates.push(carte);
+    /// Take the top `count` crates off a stack, bottom one first, leaving it as it was if
+    /// there aren't that many.
+    fn take(&mut self, count: usize, from: usize) -> Result<Vec<Crate>, String> {
+        let crates = &mut self.stacks[from].crates;
+        if crates.len() < count {
+            return Err(format!(
+                "stack {} only has {} crate(s), so {} can't be taken off it",
+                from + 1,
+                crates.len(),
+                count
+            ));
         }
+        Ok(crates.split_off(crates.len() - count))
     }
-    fn move_crate_stack(&mut self, num: i32, from: usize, to: usize) {
-        let mut tmp = vec![];
-        for _ in 0..num {
-            let carte = self.stacks[from].crates.pop().unwrap();
-            tmp.push(carte);
-        }
-        for _ in 0..num {
-            self.stacks[to].crates.push(tmp.pop().unwrap());
-        }
+    /// The yard drawn the way the puzzle input does, crates stacked up from a numbered footer.
+    /// Columns are as wide as the widest stack number needs, so this reads back in with
+    /// `load_initial_yard` however many stacks there are.
+    pub fn render(&self) -> String {
+        let width = self.stacks.len().to_string().len().max(3);
+        let centre = |text: &str| {
+            let left = (width - text.len()).div_ceil(2);
+            format!(
+                "{:left$}{}{:right$}",
+                "",
+                text,
+                "",
+                right = width - left - text.len()
+            )
+        };
+        let height = self
+            .stacks
+            .iter()
+            .map(|s| s.crates.len())
+            .max()
+            .unwrap_or(0);
+        let mut rows: Vec<String> = (0..height)
+            .rev()
+            .map(|level| {
+                self.stacks
+                    .iter()
+                    .map(|stack| match stack.crates.get(level) {
+                        Some(carte) => centre(&format!("[{}]", carte)),
+                        None => centre(""),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        rows.push(
+            (1..=self.stacks.len())
+                .map(|i| centre(&i.to_string()))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        rows.join("\n")
     }
-    fn repr(&self) -> String {
+    /// The crate on top of each stack, skipping empty ones.
+    pub fn repr(&self) -> String {
         self.stacks
             .iter()
-            .map(|stack| stack.crates.last().unwrap().to_string())
-            .fold("".to_string(), |acc, c| acc + &c)
+            .filter_map(|stack| stack.crates.last())
+            .fold("".to_string(), |acc, c| acc + c)
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct Stack {
     crates: Vec<Crate>,
 }
@@ -46,64 +87,434 @@ impl Stack {
     fn new() -> Stack {
         Stack { crates: vec![] }
     }
-    fn push_bottom(&mut self, carte: Crate) {
-        self.crates.insert(0, carte);
-    }
 }
 
 type Crate = String;
 
-fn load_initial_yard() -> Yard {
-    let crate_re: Regex = Regex::new(r"(?:\[([A-Z])\] )|(?:    )").unwrap();
+/// The starting yard from the diagram at the top of the input. The numbers along the bottom
+/// say how many stacks there are and where each one is, and every crate belongs to the
+/// stack whose number it's over.
+fn load_initial_yard(lines: &[String]) -> Result<Yard, String> {
+    let diagram: Vec<&String> = lines
+        .iter()
+        .take_while(|line| !line.trim().is_empty())
+        .collect();
+    let (footer, rows) = diagram
+        .split_last()
+        .ok_or("There's no diagram of the stacks")?;
+    if !footer.chars().all(|c| c == ' ' || c.is_ascii_digit()) {
+        return Err(format!(
+            "The last line of the diagram isn't stack numbers: {}",
+            footer
+        ));
+    }
+    let number_re = Regex::new(r"[0-9]+").unwrap();
+    // twice the column in the middle of each stack number, so it's whole for any width
+    let mut middles = vec![];
+    for (i, number) in number_re.find_iter(footer).enumerate() {
+        if number.as_str() != (i + 1).to_string() {
+            return Err(format!(
+                "Stack {} is numbered {}, they should count up from 1",
+                i + 1,
+                number.as_str()
+            ));
+        }
+        middles.push(number.start() + number.end() - 1);
+    }
+    if middles.is_empty() {
+        return Err("The diagram doesn't number any stacks".to_string());
+    }
+
+    let crate_re = Regex::new(r"\[(\S)\]").unwrap();
     let mut yard = Yard::new();
-    for _ in 0..9 {
-        // TODO don't hardcode magic numbers
+    for _ in 0..middles.len() {
         yard.stacks.push(Stack::new())
     }
-    let reader = advent::read_input(5);
-    for line in reader.iter() {
-        let line = line.to_string() + " ";
-        if line == " " {
-            break;
-        }
-        let mut i = 0;
-        for captures in crate_re.captures_iter(&line) {
-            if let Some(m) = captures.get(1) {
-                yard.stacks[i].push_bottom(m.as_str().to_string());
+    for (level, row) in rows.iter().rev().enumerate() {
+        for captures in crate_re.captures_iter(row) {
+            let name = captures.get(1).unwrap();
+            let i = (0..middles.len())
+                .min_by_key(|&i| middles[i].abs_diff(2 * name.start()))
+                .unwrap();
+            let stack = &mut yard.stacks[i];
+            if stack.crates.len() != level {
+                return Err(format!(
+                    "Crate {} isn't sitting on top of stack {}: {}",
+                    name.as_str(),
+                    i + 1,
+                    row
+                ));
             }
-            i += 1;
+            stack.crates.push(name.as_str().to_string());
         }
     }
-    yard
+    Ok(yard)
+}
+
+/// One line of the rearrangement procedure, with stacks counted from 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// The starting yard and the moves to carry out on it, read from the input once.
+#[derive(Debug, Clone)]
+pub struct Procedure {
+    pub yard: Yard,
+    pub moves: Vec<Move>,
+}
+
+impl Procedure {
+    pub fn parse(lines: &[String]) -> Result<Procedure, String> {
+        let yard = load_initial_yard(lines)?;
+        let move_re = Regex::new(r"^move ([0-9]+) from ([0-9]+) to ([0-9]+)$").unwrap();
+        let stack = |number: &str| match str::parse::<usize>(number) {
+            Ok(i) if (1..=yard.stacks.len()).contains(&i) => Ok(i - 1),
+            _ => Err(format!("There's no stack {}", number)),
+        };
+        let moves = lines
+            .iter()
+            .skip_while(|line| !line.trim().is_empty())
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let captures = move_re
+                    .captures(line)
+                    .ok_or(format!("Not a move: {}", line))?;
+                Ok(Move {
+                    count: str::parse(&captures[1]).map_err(|e| format!("{}: {}", line, e))?,
+                    from: stack(&captures[2])?,
+                    to: stack(&captures[3])?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Procedure { yard, moves })
+    }
+}
+
+/// Something that moves crates from one stack to another, however many it can lift at once.
+pub trait Crane {
+    fn name(&self) -> String;
+
+    /// The most crates it picks up in one go.
+    fn capacity(&self) -> usize;
+
+    /// Where the crates taken off a stack end up on the other, bottom one first. It picks up
+    /// as many as it can from the top each time, keeping their order.
+    fn unload(&self, taken: Vec<Crate>) -> Vec<Crate> {
+        taken
+            .rchunks(self.capacity().max(1))
+            .flat_map(|load| load.iter().cloned())
+            .collect()
+    }
+}
+
+/// Moves crates one at a time.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+    fn capacity(&self) -> usize {
+        1
+    }
+}
+
+/// Moves any number of crates at once.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
 }
 
-pub fn solve() {
-    let mut yard = load_initial_yard();
+/// A crane that can lift some other number of crates at once.
+pub struct CustomCrane(pub usize);
 
-    // part 1
-    // Borrowing is hard, so just read the damn file twice
-    let move_re = Regex::new(r"move ([0-9]+) from ([1-9]) to ([1-9])").unwrap();
-    let reader = advent::read_input(5);
-    for line in reader.iter() {
-        if let Some(captures) = move_re.captures(&line) {
-            let num = str::parse::<i32>(&captures[1]).unwrap();
-            let from = str::parse::<usize>(&captures[2]).unwrap() - 1;
-            let to = str::parse::<usize>(&captures[3]).unwrap() - 1;
-            yard.move_crates(num, from, to);
+impl Crane for CustomCrane {
+    fn name(&self) -> String {
+        format!("crane lifting {}", self.0)
+    }
+    fn capacity(&self) -> usize {
+        self.0
+    }
+}
+
+/// A crane by model number (9000, 9001) or how many crates it lifts at once.
+pub fn crane(name: &str) -> Result<Box<dyn Crane>, String> {
+    match name {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        _ => match str::parse::<usize>(name) {
+            Ok(capacity) if capacity > 0 => Ok(Box::new(CustomCrane(capacity))),
+            _ => Err(format!("not a crane: {}", name)),
+        },
+    }
+}
+
+/// A crane working through a procedure one move at a time, which can also put moves back.
+pub struct Replay<'a> {
+    procedure: &'a Procedure,
+    crane: &'a dyn Crane,
+    yard: Yard,
+    // the crates each move done so far took, as they were on the stack they came from
+    taken: Vec<Vec<Crate>>,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(procedure: &'a Procedure, crane: &'a dyn Crane) -> Replay<'a> {
+        Replay {
+            procedure,
+            crane,
+            yard: procedure.yard.clone(),
+            taken: vec![],
         }
     }
-    println!("{}", yard.repr());
-
-    // two more reads for part 2 bby
-    let mut yard = load_initial_yard();
-    let reader = advent::read_input(5);
-    for line in reader.iter() {
-        if let Some(captures) = move_re.captures(&line) {
-            let num = str::parse::<i32>(&captures[1]).unwrap();
-            let from = str::parse::<usize>(&captures[2]).unwrap() - 1;
-            let to = str::parse::<usize>(&captures[3]).unwrap() - 1;
-            yard.move_crate_stack(num, from, to);
+
+    pub fn yard(&self) -> &Yard {
+        &self.yard
+    }
+
+    /// How many moves have been done.
+    pub fn position(&self) -> usize {
+        self.taken.len()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.position() == self.procedure.moves.len()
+    }
+
+    /// Do the next move, returning false if there aren't any left. A move that takes more
+    /// crates than the stack has is an error, and the yard stays as it was.
+    pub fn step(&mut self) -> Result<bool, String> {
+        let Some(step) = self.procedure.moves.get(self.position()) else {
+            return Ok(false);
+        };
+        let taken = self
+            .yard
+            .take(step.count, step.from)
+            .map_err(|e| format!("Move {}: {}", self.position() + 1, e))?;
+        let unloaded = self.crane.unload(taken.clone());
+        self.yard.stacks[step.to].crates.extend(unloaded);
+        self.taken.push(taken);
+        Ok(true)
+    }
+
+    /// Undo the last move, returning false if there's nothing to undo.
+    pub fn back(&mut self) -> bool {
+        let Some(taken) = self.taken.pop() else {
+            return false;
+        };
+        let step = &self.procedure.moves[self.position()];
+        let to = &mut self.yard.stacks[step.to].crates;
+        to.truncate(to.len() - taken.len());
+        self.yard.stacks[step.from].crates.extend(taken);
+        true
+    }
+
+    /// Step forwards or backwards until `position` moves have been done.
+    pub fn seek(&mut self, position: usize) -> Result<(), String> {
+        while self.position() > position {
+            self.back();
+        }
+        while self.position() < position {
+            if !self.step()? {
+                return Err(format!(
+                    "There are only {} moves",
+                    self.procedure.moves.len()
+                ));
+            }
         }
+        Ok(())
+    }
+
+    /// Do every move that's left.
+    pub fn finish(&mut self) -> Result<&Yard, String> {
+        while self.step()? {
+            log::trace!("After move {}\n{}", self.position(), self.yard.render());
+        }
+        log::debug!(
+            "The {} rearranged the yard into\n{}",
+            self.crane.name(),
+            self.yard.render()
+        );
+        Ok(&self.yard)
+    }
+}
+
+fn rearrange(lines: &[String], crane: &dyn Crane) -> Result<String, String> {
+    let procedure = Procedure::parse(lines)?;
+    Ok(Replay::new(&procedure, crane).finish()?.repr())
+}
+
+pub fn solve_1(lines: &[String]) -> String {
+    rearrange(lines, &CrateMover9000).unwrap()
+}
+
+pub fn solve_2(lines: &[String]) -> String {
+    rearrange(lines, &CrateMover9001).unwrap()
+}
+
+struct ProcedureExplorer {
+    procedure: Result<Procedure, String>,
+}
+
+impl repl::Explorer for ProcedureExplorer {
+    fn help(&self) -> Vec<&'static str> {
+        vec![
+            "moves           every move in the procedure, numbered",
+            "show N [CRANE]  the yard after N moves, e.g. `show 2 9001`",
+            "run [CRANE]     the yard after each move, up to the end or a move that can't be done",
+            "                (CRANE is 9000 by default, 9001, or how many crates it lifts at once)",
+        ]
+    }
+    fn query(&self, command: &str, args: &[&str]) -> Result<String, String> {
+        let procedure = self.procedure.as_ref().map_err(Clone::clone)?;
+        let crane_arg = |i: usize| crane(args.get(i).copied().unwrap_or("9000"));
+        match command {
+            "moves" => Ok(procedure
+                .moves
+                .iter()
+                .enumerate()
+                .map(|(i, step)| {
+                    format!(
+                        "{:>4}: move {} from {} to {}",
+                        i + 1,
+                        step.count,
+                        step.from + 1,
+                        step.to + 1
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")),
+            "show" => {
+                let position = args.first().ok_or("missing number of moves")?;
+                let position = position
+                    .parse()
+                    .map_err(|_| format!("not a number of moves: {}", position))?;
+                let crane = crane_arg(1)?;
+                let mut replay = Replay::new(procedure, crane.as_ref());
+                replay.seek(position)?;
+                Ok(replay.yard().render())
+            }
+            "run" => {
+                let crane = crane_arg(0)?;
+                let mut replay = Replay::new(procedure, crane.as_ref());
+                let mut shown = vec![format!("Start\n{}", replay.yard().render())];
+                loop {
+                    match replay.step() {
+                        Ok(true) => shown.push(format!(
+                            "After move {}\n{}",
+                            replay.position(),
+                            replay.yard().render()
+                        )),
+                        Ok(false) => break,
+                        Err(e) => {
+                            shown.push(format!("error: {}", e));
+                            break;
+                        }
+                    }
+                }
+                if replay.is_done() {
+                    shown.push(format!("Tops: {}", replay.yard().repr()));
+                }
+                Ok(shown.join("\n\n"))
+            }
+            _ => Err(format!("unknown command {}", command)),
+        }
+    }
+}
+
+pub fn explorer(lines: &[String]) -> Box<dyn repl::Explorer> {
+    Box::new(ProcedureExplorer {
+        procedure: Procedure::parse(lines),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        crane, load_initial_yard, rearrange, Crane, CrateMover9000, CrateMover9001, Procedure,
+        Replay, Yard,
+    };
+    use crate::advent::{lines, read_example};
+    use crate::snapshot::assert_snapshot;
+
+    fn rearranged(procedure: &Procedure, crane: &dyn Crane) -> String {
+        Replay::new(procedure, crane).finish().unwrap().render()
+    }
+
+    #[test]
+    fn test_render() {
+        let lines = read_example(5);
+        let yard = load_initial_yard(&lines).unwrap();
+        // the diagram comes back out exactly as it went in
+        let diagram = lines.iter().take_while(|line| !line.is_empty());
+        assert_eq!(
+            yard.render(),
+            diagram.cloned().collect::<Vec<_>>().join("\n")
+        );
+        let procedure = Procedure::parse(&lines).unwrap();
+        assert_snapshot("day05_initial", &yard.render());
+        assert_snapshot("day05_part_1", &rearranged(&procedure, &CrateMover9000));
+        assert_snapshot("day05_part_2", &rearranged(&procedure, &CrateMover9001));
+    }
+
+    #[test]
+    fn test_many_stacks() {
+        let mut yard = Yard::new();
+        yard.stacks = (0..12).map(|_| super::Stack::new()).collect();
+        yard.stacks[0].crates.push("A".to_string());
+        yard.stacks[9].crates.push("B".to_string());
+        yard.stacks[11].crates = vec!["C".to_string(), "D".to_string()];
+        let mut text = yard.render();
+        assert!(text.ends_with(" 9   10  11  12"), "{}", text);
+        text.push_str("\n\nmove 2 from 12 to 10\nmove 1 from 10 to 1\n");
+        let input = lines(&text);
+        assert_eq!(load_initial_yard(&input).unwrap().render(), yard.render());
+        assert_eq!(rearrange(&input, &CrateMover9000).unwrap(), "CD");
+        assert_eq!(rearrange(&input, &CrateMover9001).unwrap(), "DC");
+
+        assert!(load_initial_yard(&lines(" 1   3 \n")).is_err());
+        assert!(load_initial_yard(&lines("[A]    \n    [B]\n 1   2 \n")).is_err());
+        assert!(Procedure::parse(&lines("[A]\n 1 \n\nmove 1 from 1 to 2")).is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        let text = "[A]        \n[B]        \n[C]        \n[D] [E]    \n 1   2   3 \n\n\
+                    move 4 from 1 to 3\nmove 2 from 2 to 1\n";
+        let procedure = Procedure::parse(&lines(text)).unwrap();
+        // a crane lifting 3 takes the top three together, then D on its own
+        let tops = |capacity: &str| {
+            let crane = crane(capacity).unwrap();
+            let mut replay = Replay::new(&procedure, crane.as_ref());
+            replay.step().unwrap();
+            replay.yard().stacks[2].crates.concat()
+        };
+        assert_eq!(tops("9000"), "ABCD");
+        assert_eq!(tops("9001"), "DCBA");
+        assert_eq!(tops("3"), "CBAD");
+        assert_eq!(tops("1"), tops("9000"));
+        assert!(crane("0").is_err());
+
+        // the second move takes more than there is, and going back undoes the first
+        let mut replay = Replay::new(&procedure, &CrateMover9001);
+        assert_eq!(replay.step(), Ok(true));
+        assert_eq!(
+            replay.step().unwrap_err(),
+            "Move 2: stack 2 only has 1 crate(s), so 2 can't be taken off it"
+        );
+        assert_eq!(replay.position(), 1);
+        assert!(replay.back());
+        assert!(!replay.back());
+        assert_eq!(replay.yard().render(), procedure.yard.render());
+        assert!(replay.seek(2).is_err());
     }
-    println!("{}", yard.repr());
 }
@@ -1,28 +1,146 @@
-use substring::Substring;
-
-use crate::advent;
-
-fn solver(sequential: usize) {
-    let line = &advent::read_input(6)[0];
-    for i in sequential..line.len() {
-        let slice = line.substring(i - sequential, i);
-        let mut duplicate = false;
-        for j in 0..sequential {
-            if slice
-                .substring(j + 1, sequential)
-                .contains(slice.chars().nth(j).unwrap())
-            {
-                duplicate = true;
+use std::io::{BufReader, Bytes, Read};
+
+/// Looks for markers, runs of `size` bytes that are all different, in bytes fed to it one at
+/// a time. It keeps count of how often each byte is in the window, so each new byte only has
+/// to update the counts of the one coming in and the one going out.
+pub struct MarkerDetector {
+    size: usize,
+    // the last `size` bytes, oldest at `position % size` once it's full
+    window: Vec<u8>,
+    counts: [usize; 256],
+    // how many byte values are in the window more than once
+    repeated: usize,
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(size: usize) -> Result<MarkerDetector, String> {
+        if size == 0 {
+            return Err("A marker needs to be at least one byte long".to_string());
+        }
+        Ok(MarkerDetector {
+            size,
+            window: Vec::with_capacity(size),
+            counts: [0; 256],
+            repeated: 0,
+            position: 0,
+        })
+    }
+
+    /// Add the next byte, returning whether it ends a marker.
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.window.len() < self.size {
+            self.window.push(byte);
+        } else {
+            let slot = self.position % self.size;
+            let old = std::mem::replace(&mut self.window[slot], byte);
+            self.counts[old as usize] -= 1;
+            if self.counts[old as usize] == 1 {
+                self.repeated -= 1;
             }
         }
-        if !duplicate {
-            println!("{}", i);
-            return;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.repeated += 1;
+        }
+        self.position += 1;
+        self.window.len() == self.size && self.repeated == 0
+    }
+
+    /// How many bytes have been added.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// Every position in a stream where a marker ends, counted in bytes from the start the way
+/// the puzzle does. Nothing is kept of the stream but the last `size` bytes.
+pub struct Markers<R> {
+    bytes: Bytes<BufReader<R>>,
+    detector: MarkerDetector,
+}
+
+impl<R: Read> Iterator for Markers<R> {
+    type Item = Result<usize, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for byte in self.bytes.by_ref() {
+            match byte {
+                Ok(byte) if self.detector.push(byte) => return Some(Ok(self.detector.position())),
+                Ok(_) => {}
+                Err(e) => return Some(Err(format!("Can't read the datastream: {}", e))),
+            }
         }
+        None
     }
 }
 
-pub fn solve() {
-    solver(4);
-    solver(14);
+pub fn markers<R: Read>(reader: R, size: usize) -> Result<Markers<R>, String> {
+    Ok(Markers {
+        bytes: BufReader::new(reader).bytes(),
+        detector: MarkerDetector::new(size)?,
+    })
+}
+
+fn solver(line: &str, size: usize) -> usize {
+    markers(line.as_bytes(), size)
+        .unwrap()
+        .next()
+        .expect("No marker found")
+        .unwrap()
+}
+
+pub fn solve_1(lines: &[String]) -> usize {
+    solver(&lines[0], 4)
+}
+
+pub fn solve_2(lines: &[String]) -> usize {
+    solver(&lines[0], 14)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{markers, solver};
+
+    /// Every marker, checking each window from scratch.
+    fn brute_force(bytes: &[u8], size: usize) -> Vec<usize> {
+        (size..=bytes.len())
+            .filter(|&end| {
+                let window = &bytes[end - size..end];
+                (0..size).all(|i| !window[i + 1..].contains(&window[i]))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markers() {
+        assert_eq!(solver("bvwbjplbgvbhsrlpgdmjqwftvncz", 4), 5);
+        assert_eq!(solver("nppdvjthqldpwncqszvftbrmjlhg", 14), 23);
+        // a marker right at the end counts
+        assert_eq!(solver("aabcd", 4), 5);
+        assert_eq!(solver("abc", 1), 1);
+
+        let all = |text: &[u8], size| {
+            markers(text, size)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+        assert_eq!(all(b"abcabca", 3), vec![3, 4, 5, 6, 7]);
+        assert_eq!(all(b"aaaa", 2), vec![]);
+        assert!(markers(&b"abc"[..], 0).is_err());
+
+        // any bytes at all, not just letters
+        let bytes: Vec<u8> = (0..5000u32).map(|i| (i * i % 251 + i % 7) as u8).collect();
+        for size in [1, 2, 4, 14, 20, 255] {
+            assert_eq!(
+                all(&bytes, size),
+                brute_force(&bytes, size),
+                "size {}",
+                size
+            );
+        }
+        let every_byte: Vec<u8> = (0..=255).collect();
+        assert_eq!(all(&every_byte, 256), vec![256]);
+    }
 }
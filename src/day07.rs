@@ -1,144 +1,431 @@
-use crate::advent;
-use regex::Regex;
-use substring::Substring;
+use crate::repl;
+
+/// Where a node is kept in its filesystem.
+pub type NodeId = usize;
 
 #[derive(Debug)]
-struct Dir {
-    name: String,
-    files: Vec<File>,
-    dirs: Vec<Dir>,
-    file_size: u32,
+pub enum Kind {
+    File,
+    /// The directory's entries, in the order they were listed.
+    Dir(Vec<NodeId>),
 }
 
-impl Dir {
-    fn new(name: &str) -> Dir {
-        Dir {
-            name: name.to_string(),
-            files: vec![],
-            dirs: vec![],
-            file_size: 0,
+#[derive(Debug)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: Kind,
+    // a file's own size, or everything under a directory, kept up to date as nodes are added
+    size: u64,
+}
+
+/// How to order the entries of a directory in reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    /// The order they were listed in.
+    Listed,
+    Name,
+    /// Biggest first.
+    Size,
+}
+
+impl Sort {
+    pub fn parse(sort: &str) -> Result<Sort, String> {
+        match sort {
+            "listed" => Ok(Sort::Listed),
+            "name" => Ok(Sort::Name),
+            "size" => Ok(Sort::Size),
+            _ => Err(format!("can't sort by {}, only listed, name or size", sort)),
         }
     }
-    fn lookup(&mut self, path: &Vec<String>) -> &mut Dir {
-        if path.is_empty() {
-            return self;
+}
+
+/// Every file and directory there is, kept in one list and referring to each other by where
+/// they are in it. The root directory is always first.
+#[derive(Debug)]
+pub struct Filesystem {
+    nodes: Vec<Node>,
+}
+
+impl Filesystem {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> Filesystem {
+        Filesystem {
+            nodes: vec![Node {
+                name: String::new(),
+                parent: None,
+                kind: Kind::Dir(vec![]),
+                size: 0,
+            }],
         }
-        let mut path = path.clone();
-        let dirname = path.remove(0);
-        for dir in self.dirs.iter_mut() {
-            if dir.name == dirname {
-                return dir.lookup(&path);
+    }
+
+    /// Rebuild the filesystem from the output of the `cd` and `ls` commands it was explored
+    /// with. Directories can be listed more than once, and `cd` into one that wasn't listed
+    /// creates it.
+    pub fn parse(lines: &[String]) -> Result<Filesystem, String> {
+        let mut fs = Filesystem::new();
+        let mut cwd = Filesystem::ROOT;
+        for line in lines {
+            let error = |e: String| format!("{}: {}", line, e);
+            if let Some(dir) = line.strip_prefix("$ cd ") {
+                cwd = match dir {
+                    "/" => Filesystem::ROOT,
+                    ".." => fs.nodes[cwd]
+                        .parent
+                        .ok_or_else(|| error("/ has no parent".to_string()))?,
+                    _ if dir.starts_with('/') => fs.lookup(dir).map_err(error)?,
+                    _ => fs.mkdir(cwd, dir).map_err(error)?,
+                };
+            } else if line == "$ ls" {
+                // the entries come on the lines after
+            } else if let Some(name) = line.strip_prefix("dir ") {
+                fs.mkdir(cwd, name).map_err(error)?;
+            } else if let Some((size, name)) = line.split_once(' ') {
+                let size = size
+                    .parse()
+                    .map_err(|_| format!("Not a file size or command: {}", line))?;
+                fs.add_file(cwd, name, size).map_err(error)?;
+            } else if !line.is_empty() {
+                return Err(format!("Not a command or directory entry: {}", line));
             }
         }
-        panic!("File not found")
+        Ok(fs)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, Kind::Dir(_))
+    }
+
+    /// A file's size, or the total size of everything under a directory.
+    pub fn size(&self, id: NodeId) -> u64 {
+        self.nodes[id].size
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.nodes[id].kind {
+            Kind::Dir(children) => children,
+            Kind::File => &[],
+        }
     }
-    fn add_file(&mut self, file: File) {
-        self.file_size += file.size;
-        self.files.push(file);
+
+    pub fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.children(dir)
+            .iter()
+            .copied()
+            .find(|&id| self.nodes[id].name == name)
     }
-    fn add_dir(&mut self, dir: Dir) {
-        self.dirs.push(dir);
+
+    /// Every directory, the root first.
+    pub fn dirs(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|&id| self.is_dir(id))
     }
-    fn size(&self) -> u32 {
-        let mut size = self.file_size;
-        for dir in self.dirs.iter() {
-            size += dir.size();
+
+    /// Add a directory, or find the one that's already there.
+    pub fn mkdir(&mut self, dir: NodeId, name: &str) -> Result<NodeId, String> {
+        match self.child(dir, name) {
+            Some(id) if self.is_dir(id) => Ok(id),
+            Some(_) => Err(format!("{} is a file", self.path_to(dir, name))),
+            None => self.add(dir, name, Kind::Dir(vec![]), 0),
         }
-        size
     }
-}
 
-#[derive(Debug)]
-struct File {
-    #[allow(dead_code)]
-    name: String,
-    size: u32,
-}
+    /// Add a file, or check it's the same size as the one that's already there.
+    pub fn add_file(&mut self, dir: NodeId, name: &str, size: u64) -> Result<NodeId, String> {
+        match self.child(dir, name) {
+            Some(id) if !self.is_dir(id) && self.size(id) == size => Ok(id),
+            Some(_) => Err(format!(
+                "{} was already listed as something else",
+                self.path_to(dir, name)
+            )),
+            None => self.add(dir, name, Kind::File, size),
+        }
+    }
 
-impl File {
-    fn new(name: &str, size: u32) -> File {
-        File {
+    fn add(&mut self, dir: NodeId, name: &str, kind: Kind, size: u64) -> Result<NodeId, String> {
+        if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+            return Err(format!(
+                "{:?} can't be the name of a file or directory",
+                name
+            ));
+        }
+        let id = self.nodes.len();
+        match &mut self.nodes[dir].kind {
+            Kind::Dir(children) => children.push(id),
+            Kind::File => return Err(format!("{} isn't a directory", self.path(dir))),
+        }
+        self.nodes.push(Node {
             name: name.to_string(),
+            parent: Some(dir),
+            kind,
             size,
+        });
+        let mut ancestor = Some(dir);
+        while let Some(id) = ancestor {
+            self.nodes[id].size += size;
+            ancestor = self.nodes[id].parent;
         }
+        Ok(id)
     }
-}
 
-fn build_fs() -> Dir {
-    let file_re = Regex::new(r"^([0-9]+) ([a-z\.]+)$").unwrap();
-    let dir_re = Regex::new(r"^dir ([a-z]+)$").unwrap();
-    let lines = advent::read_input(7);
-    let mut iter = lines.iter();
-    let mut root = Dir::new("/");
-    let mut path: Vec<String> = vec![];
-    while let Some(line) = iter.next() {
-        match line.as_str() {
-            "$ cd .." => {
-                path.pop();
-            }
-            "$ cd /" => {
-                path = vec![];
-            }
-            "$ ls" => {
-                // we don't actually need to do anything here lol
-            }
-            _ => {
-                if line.starts_with("$ cd ") {
-                    let dir = line.substring(5, line.len());
-                    path.push(dir.to_string());
-                } else {
-                    let dir = root.lookup(&path);
-                    if let Some(captures) = file_re.captures(&line) {
-                        let size = str::parse(&captures[1]).unwrap();
-                        let name = &captures[2];
-                        dir.add_file(File::new(name, size));
-                    } else if let Some(captures) = dir_re.captures(&line) {
-                        let name = &captures[1];
-                        dir.add_dir(Dir::new(name));
-                    }
-                    // println!("NEEW {:?}", dir);
-                }
+    /// The absolute path of a node, e.g. `/a/e`.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut node = Some(id);
+        while let Some(id) = node {
+            names.push(self.nodes[id].name.as_str());
+            node = self.nodes[id].parent;
+        }
+        match names.len() {
+            1 => "/".to_string(),
+            _ => names.into_iter().rev().collect::<Vec<_>>().join("/"),
+        }
+    }
+
+    fn path_to(&self, dir: NodeId, name: &str) -> String {
+        format!("{}/{}", self.path(dir).trim_end_matches('/'), name)
+    }
+
+    /// The node at an absolute path. `.` and `..` work the way they do in a shell.
+    pub fn lookup(&self, path: &str) -> Result<NodeId, String> {
+        if !path.starts_with('/') {
+            return Err(format!("{} isn't an absolute path", path));
+        }
+        let mut id = Filesystem::ROOT;
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            id = match name {
+                "." => id,
+                ".." => self.nodes[id].parent.unwrap_or(id),
+                _ => self
+                    .child(id, name)
+                    .ok_or(format!("no such file or directory: {}", path))?,
+            };
+        }
+        Ok(id)
+    }
+
+    fn sorted_children(&self, id: NodeId, sort: Sort) -> Vec<NodeId> {
+        let mut children = self.children(id).to_vec();
+        match sort {
+            Sort::Listed => {}
+            Sort::Name => children.sort_by(|&a, &b| self.nodes[a].name.cmp(&self.nodes[b].name)),
+            Sort::Size => children.sort_by_key(|&id| std::cmp::Reverse(self.size(id))),
+        }
+        children
+    }
+
+    /// The directories under `id` (and `id` itself) at most `depth` levels down, with their
+    /// sizes, the way `du --max-depth` shows them: everything in a directory comes before it.
+    pub fn du(&self, id: NodeId, depth: Option<usize>, sort: Sort) -> String {
+        let mut lines = vec![];
+        self.du_into(id, 0, depth, sort, &mut lines);
+        lines.join("\n")
+    }
+
+    fn du_into(
+        &self,
+        id: NodeId,
+        level: usize,
+        depth: Option<usize>,
+        sort: Sort,
+        lines: &mut Vec<String>,
+    ) {
+        if depth.is_some_and(|depth| level > depth) {
+            return;
+        }
+        for child in self.sorted_children(id, sort) {
+            if self.is_dir(child) {
+                self.du_into(child, level + 1, depth, sort, lines);
             }
+        }
+        lines.push(format!("{}\t{}", self.size(id), self.path(id)));
+    }
+
+    /// Everything under `id`, at most `depth` levels down, drawn the way the puzzle does.
+    pub fn tree(&self, id: NodeId, depth: Option<usize>, sort: Sort) -> String {
+        let mut lines = vec![];
+        self.tree_into(id, 0, depth, sort, &mut lines);
+        lines.join("\n")
+    }
+
+    fn tree_into(
+        &self,
+        id: NodeId,
+        level: usize,
+        depth: Option<usize>,
+        sort: Sort,
+        lines: &mut Vec<String>,
+    ) {
+        let name = match id {
+            Filesystem::ROOT => "/",
+            _ => &self.nodes[id].name,
         };
+        let kind = if self.is_dir(id) { "dir" } else { "file" };
+        lines.push(format!(
+            "{}- {} ({}, size={})",
+            "  ".repeat(level),
+            name,
+            kind,
+            self.size(id)
+        ));
+        if depth.is_some_and(|depth| level >= depth) {
+            return;
+        }
+        for child in self.sorted_children(id, sort) {
+            self.tree_into(child, level + 1, depth, sort, lines);
+        }
     }
-    root
 }
 
-fn solve_1(dir: &Dir) -> u32 {
-    let mut total_return = 0;
-    if dir.size() < 100000 {
-        total_return += dir.size();
+impl Default for Filesystem {
+    fn default() -> Filesystem {
+        Filesystem::new()
     }
-    for subdir in dir.dirs.iter() {
-        total_return += solve_1(subdir);
+}
+
+pub fn solve_1(lines: &[String]) -> u64 {
+    let fs = Filesystem::parse(lines).unwrap();
+    fs.dirs()
+        .map(|id| fs.size(id))
+        .filter(|&size| size <= 100000)
+        .sum()
+}
+
+pub fn solve_2(lines: &[String]) -> u64 {
+    const TOTAL_SIZE: u64 = 70000000;
+    const REQUIRED_FREE_SPACE: u64 = 30000000;
+    let fs = Filesystem::parse(lines).unwrap();
+    let needed = (fs.size(Filesystem::ROOT) + REQUIRED_FREE_SPACE).saturating_sub(TOTAL_SIZE);
+    fs.dirs()
+        .map(|id| fs.size(id))
+        .filter(|&size| size >= needed)
+        .min()
+        .unwrap()
+}
+
+struct FsExplorer {
+    fs: Result<Filesystem, String>,
+}
+
+/// What the `du` and `tree` commands were asked for: a path, then `-d DEPTH` and `-s SORT`
+/// in any order.
+fn report_args(fs: &Filesystem, args: &[&str]) -> Result<(NodeId, Option<usize>, Sort), String> {
+    let (mut id, mut depth, mut sort) = (Filesystem::ROOT, None, Sort::Listed);
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "-d" => {
+                let value = args.next().ok_or("-d needs a depth")?;
+                depth = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("not a depth: {}", value))?,
+                );
+            }
+            "-s" => sort = Sort::parse(args.next().ok_or("-s needs listed, name or size")?)?,
+            _ => id = fs.lookup(arg)?,
+        }
     }
-    total_return
+    Ok((id, depth, sort))
 }
 
-fn _walk_2(dir: &Dir, min_dir_size: u32) -> Option<u32> {
-    let mut smallest_dir_size = dir.size();
-    if smallest_dir_size < min_dir_size {
-        return None;
+impl repl::Explorer for FsExplorer {
+    fn help(&self) -> Vec<&'static str> {
+        vec![
+            "size PATH    total size of a directory, e.g. `size /a/e`",
+            "ls PATH      list a directory",
+            "du [PATH] [-d DEPTH] [-s listed|name|size]    sizes of the directories under PATH",
+            "tree [PATH] [-d DEPTH] [-s listed|name|size]  everything under PATH",
+        ]
     }
-    for subdir in dir.dirs.iter() {
-        if let Some(subdir_size) = _walk_2(subdir, min_dir_size) {
-            if subdir_size > min_dir_size && subdir_size < smallest_dir_size {
-                smallest_dir_size = subdir_size;
+    fn query(&self, command: &str, args: &[&str]) -> Result<String, String> {
+        let fs = self.fs.as_ref().map_err(Clone::clone)?;
+        let find = |path: Option<&&str>| fs.lookup(path.ok_or("missing path")?);
+        match command {
+            "size" => Ok(fs.size(find(args.first())?).to_string()),
+            "ls" => {
+                let dir = find(args.first())?;
+                if !fs.is_dir(dir) {
+                    return Err(format!("not a directory: {}", fs.path(dir)));
+                }
+                let (dirs, files): (Vec<NodeId>, Vec<NodeId>) =
+                    fs.children(dir).iter().partition(|&&id| fs.is_dir(id));
+                let dirs = dirs.iter().map(|&id| format!("dir {}", fs.node(id).name));
+                let files = files
+                    .iter()
+                    .map(|&id| format!("{} {}", fs.size(id), fs.node(id).name));
+                Ok(dirs.chain(files).collect::<Vec<String>>().join("\n"))
+            }
+            "du" => {
+                let (id, depth, sort) = report_args(fs, args)?;
+                Ok(fs.du(id, depth, sort))
+            }
+            "tree" => {
+                let (id, depth, sort) = report_args(fs, args)?;
+                Ok(fs.tree(id, depth, sort))
             }
+            _ => Err(format!("unknown command {}", command)),
         }
     }
-    Some(smallest_dir_size)
 }
 
-fn solve_2(root: &Dir) {
-    const TOTAL_SIZE: u32 = 70000000;
-    const REQUIRED_FREE_SPACE: u32 = 30000000;
-    let min_dir_size = root.size() - (TOTAL_SIZE - REQUIRED_FREE_SPACE);
-    println!("{}", _walk_2(root, min_dir_size).unwrap());
+pub fn explorer(lines: &[String]) -> Box<dyn repl::Explorer> {
+    Box::new(FsExplorer {
+        fs: Filesystem::parse(lines),
+    })
 }
 
-pub fn solve() {
-    let root = build_fs();
-    println!("{}", solve_1(&root));
-    solve_2(&root);
+#[cfg(test)]
+mod test {
+    use super::{Filesystem, Sort};
+    use crate::advent::{lines, read_example};
+    use crate::snapshot::assert_snapshot;
+
+    #[test]
+    fn test_filesystem() {
+        let fs = Filesystem::parse(&read_example(7)).unwrap();
+        let size = |path| fs.size(fs.lookup(path).unwrap());
+        assert_eq!(size("/"), 48381165);
+        assert_eq!(size("/a"), 94853);
+        assert_eq!(size("/a/e"), 584);
+        assert_eq!(size("/a/e/../../d/d.log"), 8033020);
+        assert_eq!(fs.path(fs.lookup("/a/./e/i").unwrap()), "/a/e/i");
+        assert!(fs.lookup("/a/x").is_err());
+        assert!(fs.lookup("a").is_err());
+        assert_eq!(
+            fs.du(Filesystem::ROOT, None, Sort::Listed),
+            "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/"
+        );
+        assert_eq!(
+            fs.du(Filesystem::ROOT, Some(1), Sort::Size),
+            "24933642\t/d\n94853\t/a\n48381165\t/"
+        );
+        assert_snapshot("day07_tree", &fs.tree(Filesystem::ROOT, None, Sort::Name));
+        assert_eq!(
+            fs.tree(fs.lookup("/a").unwrap(), Some(0), Sort::Listed),
+            "- a (dir, size=94853)"
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        let fs = Filesystem::parse(&lines(
+            "$ cd /\n$ ls\ndir v1.2\n10 read me\n$ cd v1.2\n$ ls\n20 2022\n$ ls\n20 2022\n\
+             $ cd /v1.2\n$ cd ..\n$ ls\n10 read me",
+        ))
+        .unwrap();
+        // listing things twice doesn't count them twice
+        assert_eq!(fs.size(Filesystem::ROOT), 30);
+        assert_eq!(fs.size(fs.lookup("/v1.2/2022").unwrap()), 20);
+        assert_eq!(fs.size(fs.lookup("/read me").unwrap()), 10);
+        assert!(Filesystem::parse(&lines("$ ls\n10 a\n20 a")).is_err());
+        assert!(Filesystem::parse(&lines("$ ls\n10 a\n$ cd a")).is_err());
+        assert!(Filesystem::parse(&lines("$ cd ..")).is_err());
+        assert!(Filesystem::parse(&lines("dir a/b")).is_err());
+    }
 }
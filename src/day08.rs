@@ -1,4 +1,3 @@
-use crate::advent;
 use std::ops::Add;
 
 #[derive(Debug)]
@@ -17,8 +16,7 @@ impl Tree {
 
 type Croft = Vec<Vec<Tree>>;
 
-fn load_data() -> Croft {
-    let lines = advent::read_input(8);
+fn load_data(lines: &[String]) -> Croft {
     lines
         .iter()
         .map(|line| {
@@ -29,7 +27,7 @@ fn load_data() -> Croft {
         .collect()
 }
 
-fn solve_1(croft: &mut Croft) -> u32 {
+fn count_visible(croft: &mut Croft) -> u32 {
     macro_rules! check_tree {
         ($tree:ident, $occluder:ident) => {
             if $tree.height as i32 > $occluder {
@@ -81,7 +79,7 @@ fn solve_1(croft: &mut Croft) -> u32 {
         .unwrap()
 }
 
-fn solve_2(croft: Croft) -> u32 {
+fn best_scenic_score(croft: Croft) -> u32 {
     let mut top_score = 0;
     for y in 1..(croft.len() - 1) {
         let row = croft.get(y).unwrap();
@@ -129,9 +127,10 @@ fn solve_2(croft: Croft) -> u32 {
     top_score as u32
 }
 
-pub fn solve() {
-    let mut croft = load_data();
+pub fn solve_1(lines: &[String]) -> u32 {
+    count_visible(&mut load_data(lines))
+}
 
-    println!("{}", solve_1(&mut croft));
-    println!("{}", solve_2(croft))
+pub fn solve_2(lines: &[String]) -> u32 {
+    best_scenic_score(load_data(lines))
 }
@@ -1,6 +1,5 @@
 use std::collections::HashSet;
 
-use crate::advent;
 use substring::Substring;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -26,8 +25,7 @@ impl Direction {
 #[derive(Debug, PartialEq, Eq, Hash)]
 struct Move(Direction, i32);
 
-fn read_input() -> Vec<Move> {
-    let lines = advent::read_input(9);
+fn read_moves(lines: &[String]) -> Vec<Move> {
     lines
         .iter()
         .map(|line| {
@@ -111,7 +109,8 @@ impl Snake {
     }
 }
 
-fn solve_1(moves: &Vec<Move>) {
+pub fn solve_1(lines: &[String]) -> usize {
+    let moves = read_moves(lines);
     let mut snake = Snake::new();
     let mut points = HashSet::new();
     points.insert(snake.tail_position());
@@ -125,7 +124,7 @@ fn solve_1(moves: &Vec<Move>) {
             }
         }
     }
-    println!("{}", points.len());
+    points.len()
 }
 #[allow(dead_code)]
 fn showit(snakes: &Vec<Snake>, points: &HashSet<(i32, i32)>) {
@@ -161,7 +160,8 @@ D 10
 L 25
 U 20
  */
-fn solve_2(moves: &Vec<Move>) {
+pub fn solve_2(lines: &[String]) -> usize {
+    let moves = read_moves(lines);
     let mut snakes = vec![];
     for _ in 0..9 {
         snakes.push(Snake::new());
@@ -183,13 +183,7 @@ fn solve_2(moves: &Vec<Move>) {
             }
         }
     }
-    println!("{}", points.len());
-}
-
-pub fn solve() {
-    let moves = read_input();
-    solve_1(&moves);
-    solve_2(&moves);
+    points.len()
 }
 
 #[cfg(test)]
@@ -1,8 +1,6 @@
-use crate::advent;
 use substring::Substring;
 
-fn run_cpu() -> Vec<i32> {
-    let lines = advent::read_input(10);
+fn run_cpu(lines: &[String]) -> Vec<i32> {
     let mut x = 1;
     let mut values = vec![];
     for line in lines.iter() {
@@ -17,33 +15,46 @@ fn run_cpu() -> Vec<i32> {
     values
 }
 
-fn print_crt(values: Vec<i32>) {
+fn render_crt(values: Vec<i32>) -> String {
+    let mut crt = String::new();
     let mut x = 0;
     for v in values.iter() {
         if *v == x - 1 || *v == x || *v == x + 1 {
-            print!("#");
+            crt.push('#');
         } else {
-            print!(".");
+            crt.push('.');
         }
         x += 1;
         if x >= 40 {
             x = 0;
-            println!();
+            crt.push('\n');
         }
     }
+    crt.trim_end().to_string()
 }
 
-pub fn solve() {
-    let values = run_cpu();
-    println!(
-        "{}",
-        (20 * values[20 - 1])
-            + (60 * values[60 - 1])
-            + (100 * values[100 - 1])
-            + (140 * values[140 - 1])
-            + (180 * values[180 - 1])
-            + (220 * values[220 - 1])
-    );
+pub fn solve_1(lines: &[String]) -> i32 {
+    let values = run_cpu(lines);
+    (20 * values[20 - 1])
+        + (60 * values[60 - 1])
+        + (100 * values[100 - 1])
+        + (140 * values[140 - 1])
+        + (180 * values[180 - 1])
+        + (220 * values[220 - 1])
+}
+
+pub fn solve_2(lines: &[String]) -> String {
+    render_crt(run_cpu(lines))
+}
 
-    print_crt(values);
+#[cfg(test)]
+mod test {
+    use super::solve_2;
+    use crate::advent::read_example;
+    use crate::snapshot::assert_snapshot;
+
+    #[test]
+    fn test_crt() {
+        assert_snapshot("day10_crt", &solve_2(&read_example(10)));
+    }
 }
@@ -1,6 +1,11 @@
-use crate::advent;
+#![cfg_attr(not(feature = "bigint"), allow(clippy::clone_on_copy))]
+
+use crate::bigint;
 use regex::{Captures, Regex};
 
+/// Worry levels get squared every few throws, which quickly outgrows a u64 on larger inputs
+type Worry = bigint::Unsigned;
+
 #[derive(Debug, Clone)]
 enum Operation {
     ADD,
@@ -14,15 +19,15 @@ impl Operation {
             _ => panic!("Unknown operator {}", c),
         }
     }
-    fn apply(&self, left: u64, right: Option<u64>) -> u64 {
-        let right = right.unwrap_or(left);
+    fn apply(&self, left: Worry, right: &Option<Worry>) -> Worry {
+        let right = right.clone().unwrap_or_else(|| left.clone());
         match self {
             Operation::ADD => left + right,
             Operation::MULTIPLY => left * right,
         }
     }
-    fn apply_mod(&self, left: u64, right: Option<u64>, modulus: u64) -> u64 {
-        let right = right.unwrap_or(left);
+    fn apply_mod(&self, left: Worry, right: &Option<Worry>, modulus: &Worry) -> Worry {
+        let right = right.clone().unwrap_or_else(|| left.clone());
         match self {
             Operation::ADD => (left % modulus) + (right % modulus),
             Operation::MULTIPLY => (left % modulus) * (right % modulus),
@@ -32,10 +37,10 @@ impl Operation {
 
 #[derive(Debug, Clone)]
 struct Monkey {
-    items: Vec<u64>,
+    items: Vec<Worry>,
     operation: Operation,
-    operand: Option<u64>,
-    divisor: u64,
+    operand: Option<Worry>,
+    divisor: Worry,
     true_target: usize,
     false_target: usize,
     inspections: u64,
@@ -46,7 +51,7 @@ impl Monkey {
         Monkey {
             items: (&capture[1])
                 .split(", ")
-                .map(str::parse::<u64>)
+                .map(str::parse::<Worry>)
                 .map(Result::unwrap)
                 .collect(),
             operation: Operation::from(&capture[2]),
@@ -61,16 +66,16 @@ impl Monkey {
         for i in 0..monkeys.len() {
             while !monkeys[i].items.is_empty() {
                 let monkey: Monkey;
-                let item: u64;
+                let item: Worry;
                 {
                     let mut_monkey = &mut monkeys[i];
                     item = mut_monkey.items.remove(0);
                     mut_monkey.inspections += 1;
                     monkey = mut_monkey.clone();
                 }
-                let item = monkey.operation.apply(item, monkey.operand);
+                let item = monkey.operation.apply(item, &monkey.operand);
                 let item = item / 3;
-                if item % monkey.divisor == 0 {
+                if bigint::is_multiple_of(&item, &monkey.divisor) {
                     monkeys[monkey.true_target].items.push(item);
                 } else {
                     monkeys[monkey.false_target].items.push(item);
@@ -78,21 +83,21 @@ impl Monkey {
             }
         }
     }
-    fn round_2(monkeys: &mut Vec<Monkey>, modulus: u64) {
+    fn round_2(monkeys: &mut Vec<Monkey>, modulus: &Worry) {
         for i in 0..monkeys.len() {
             while !monkeys[i].items.is_empty() {
                 let monkey: Monkey;
-                let item: u64;
+                let item: Worry;
                 {
                     let mut_monkey = &mut monkeys[i];
                     item = mut_monkey.items.remove(0);
                     mut_monkey.inspections += 1;
                     monkey = mut_monkey.clone();
                 }
-                let item = monkey.operation.apply_mod(item, monkey.operand, modulus);
+                let item = monkey.operation.apply_mod(item, &monkey.operand, modulus);
                 // no reductions now
                 // let item = item / 3;
-                if item % monkey.divisor == 0 {
+                if bigint::is_multiple_of(&item, &monkey.divisor) {
                     monkeys[monkey.true_target].items.push(item);
                 } else {
                     monkeys[monkey.false_target].items.push(item);
@@ -102,13 +107,13 @@ impl Monkey {
     }
 }
 
-fn get_monkeys() -> (Vec<Monkey>, u64) {
-    let file = advent::read_input(11).join("\n");
+fn get_monkeys(lines: &[String]) -> (Vec<Monkey>, Worry) {
+    let file = lines.join("\n");
     let monkey_re = Regex::new(r"Monkey [0-9]:\n  Starting items: ([0-9, ]+)\n  Operation: new = old ([\*\+]) ([0-9old]+)\n  Test: divisible by ([0-9]+)\n    If true: throw to monkey ([0-9])\n    If false: throw to monkey ([0-9])").unwrap();
     let monkeys: Vec<Monkey> = monkey_re.captures_iter(&file).map(Monkey::new).collect();
     let modulus = monkeys
         .iter()
-        .map(|m| m.divisor)
+        .map(|m| m.divisor.clone())
         .reduce(|a, b| a * b)
         .unwrap();
     (monkeys, modulus)
@@ -130,18 +135,18 @@ fn monkey_business(monkeys: &Vec<Monkey>) -> u64 {
     max_inspections_1 * max_inspections_2
 }
 
-pub fn solve() {
-    // part 1
-    let (mut monkeys, _modulus) = get_monkeys();
+pub fn solve_1(lines: &[String]) -> u64 {
+    let (mut monkeys, _modulus) = get_monkeys(lines);
     for _ in 0..20 {
         Monkey::round_1(&mut monkeys);
     }
-    println!("{}", monkey_business(&monkeys));
+    monkey_business(&monkeys)
+}
 
-    // part 2
-    let (mut monkeys, modulus) = get_monkeys();
+pub fn solve_2(lines: &[String]) -> u64 {
+    let (mut monkeys, modulus) = get_monkeys(lines);
     for _ in 0..10000 {
-        Monkey::round_2(&mut monkeys, modulus);
+        Monkey::round_2(&mut monkeys, &modulus);
     }
-    println!("{}", monkey_business(&monkeys));
+    monkey_business(&monkeys)
 }
@@ -1,4 +1,4 @@
-use crate::advent;
+use rayon::prelude::*;
 
 #[derive(Debug, Clone)]
 struct Cell {
@@ -40,8 +40,8 @@ impl Cell {
 
 type Grid = Vec<Vec<Cell>>;
 
-fn read_grid() -> (Grid, (usize, usize), (usize, usize)) {
-    let grid: Vec<Vec<char>> = advent::read_input(12)
+fn read_grid(lines: &[String]) -> (Grid, (usize, usize), (usize, usize)) {
+    let grid: Vec<Vec<char>> = lines
         .iter()
         .map(|line| line.chars().collect::<Vec<char>>())
         .collect();
@@ -76,27 +76,32 @@ fn walk_cell(grid: &mut Grid, pos: (usize, usize)) {
     let initial_distance = grid[y][x].distance;
     for adjacent in Cell::walkable_adjacents(pos, grid) {
         let (x, y) = adjacent;
-        let mut cell = &mut grid[y][x];
+        let cell = &mut grid[y][x];
         if cell.distance > initial_distance + 1 {
             cell.distance = initial_distance + 1;
             walk_cell(grid, adjacent);
         }
     }
 }
-pub fn solve() {
-    let (mut grid, start, end) = read_grid();
-    println!("{:?}", walk_grid(&mut grid, start, end));
+pub fn solve_1(lines: &[String]) -> u32 {
+    let (mut grid, start, end) = read_grid(lines);
+    walk_grid(&mut grid, start, end)
+}
 
-    let mut shortest_distance = std::u32::MAX;
+pub fn solve_2(lines: &[String]) -> u32 {
+    let (grid, _start, end) = read_grid(lines);
+    let mut starts = vec![];
     for y in 0..grid.len() {
         for x in 0..grid[y].len() {
             if grid[y][x].height == 'a' as u32 {
-                let distance = walk_grid(&mut grid.clone(), (x, y), end);
-                if distance < shortest_distance {
-                    shortest_distance = distance;
-                }
+                starts.push((x, y));
             }
         }
     }
-    println!("{}", shortest_distance);
+    // Every walk gets its own copy of the grid, so they can all happen at once
+    starts
+        .par_iter()
+        .map(|start| walk_grid(&mut grid.clone(), *start, end))
+        .min()
+        .unwrap()
 }
@@ -1,4 +1,3 @@
-use crate::advent;
 use regex::Regex;
 use std::{cmp::Ordering, fmt::Debug};
 use substring::{self, Substring};
@@ -104,9 +103,8 @@ impl Expr {
     }
 }
 
-fn solve_1() {
-    let file = advent::read_input(13);
-    let mut lines = file.iter();
+pub fn solve_1(lines: &[String]) -> usize {
+    let mut lines = lines.iter();
     let mut index = 1;
     let mut sum = 0;
     loop {
@@ -122,12 +120,11 @@ fn solve_1() {
             break;
         }
     }
-    println!("{}", sum);
+    sum
 }
 
-fn solve_2() {
-    let file = advent::read_input(13);
-    let mut lines = file.iter();
+pub fn solve_2(lines: &[String]) -> usize {
+    let mut lines = lines.iter();
     let mut exprs = vec![];
     loop {
         let l1 = lines.next().unwrap();
@@ -157,10 +154,5 @@ fn solve_2() {
             b = i + 1;
         }
     }
-    println!("{}", a * b);
-}
-
-pub fn solve() {
-    solve_1();
-    solve_2();
+    a * b
 }
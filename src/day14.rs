@@ -1,4 +1,3 @@
-use crate::advent;
 use regex::Regex;
 use std::fmt::Debug;
 
@@ -39,9 +38,9 @@ impl Dimensions {
     }
 }
 
-fn parse_lines() -> Rocks {
+fn parse_lines(lines: &[String]) -> Rocks {
     let re = Regex::new(r"(\d+),(\d+)").unwrap();
-    advent::read_input(14)
+    lines
         .iter()
         .map(|line| {
             re.captures_iter(&line)
@@ -156,18 +155,18 @@ impl Debug for Cave {
     }
 }
 
-fn solve_1() {
-    let mut cave = Cave::new(&parse_lines());
+pub fn solve_1(lines: &[String]) -> i32 {
+    let mut cave = Cave::new(&parse_lines(lines));
     let mut i = 0;
     let mut path = vec![(500, 0)];
     while cave.drop_sand(&mut path) {
         i += 1;
     }
-    println!("{}", i);
+    i
 }
 
-fn solve_2() {
-    let mut rocks = parse_lines();
+pub fn solve_2(lines: &[String]) -> i32 {
+    let mut rocks = parse_lines(lines);
     // Add the floor
     let d = Dimensions::new(&rocks);
     rocks.push(vec![
@@ -180,10 +179,21 @@ fn solve_2() {
     while cave.drop_sand(&mut path) {
         i += 1;
     }
-    println!("{}", i);
+    i
 }
 
-pub fn solve() {
-    solve_1();
-    solve_2();
+#[cfg(test)]
+mod test {
+    use super::{parse_lines, Cave};
+    use crate::advent::read_example;
+    use crate::snapshot::assert_snapshot;
+
+    #[test]
+    fn test_render() {
+        let mut cave = Cave::new(&parse_lines(&read_example(14)));
+        assert_snapshot("day14_rocks", &format!("{:?}", cave));
+        let mut path = vec![(500, 0)];
+        while cave.drop_sand(&mut path) {}
+        assert_snapshot("day14_sand", &format!("{:?}", cave));
+    }
 }
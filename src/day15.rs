@@ -1,7 +1,9 @@
-use crate::advent;
+use crate::intervals::IntervalSet;
+use rayon::prelude::*;
 use regex::Regex;
+use std::collections::HashSet;
 use std::fmt::Debug;
-use std::ops::Add;
+use std::ops::RangeInclusive;
 
 #[derive(Debug, PartialEq, Eq)]
 struct Sensor {
@@ -15,24 +17,19 @@ impl Sensor {
     fn distance(&self) -> i64 {
         return (self.sx - self.bx).abs() + (self.sy - self.by).abs();
     }
-    fn range(&self, row: i64) -> Option<(i64, i64)> {
-        let sd = self.distance();
-        let dy = (self.sy - row).abs();
-        if sd < dy {
-            None
-        } else {
-            let dx = sd - dy;
-            Some((self.sx - dx, self.sx + dx))
-        }
+    /// The part of a row the sensor can see, which is empty if the row is out of its reach.
+    fn range(&self, row: i64) -> RangeInclusive<i64> {
+        let dx = self.distance() - (self.sy - row).abs();
+        self.sx - dx..=self.sx + dx
     }
 }
 
-fn parse_input() -> Vec<Sensor> {
+fn parse_input(lines: &[String]) -> Vec<Sensor> {
     let re = Regex::new(
         r"Sensor at x=([-\d]+), y=([-\d]+): closest beacon is at x=([-\d]+), y=([-\d]+)",
     )
     .unwrap();
-    advent::read_input(15)
+    lines
         .iter()
         .map(|line| {
             let capture = re.captures(line).unwrap();
@@ -46,73 +43,43 @@ fn parse_input() -> Vec<Sensor> {
         .collect()
 }
 
-fn merge_ranges(ranges: &Vec<(i64, i64)>) -> Vec<(i64, i64)> {
-    let mut ranges = ranges.clone();
-    let mut new = vec![];
-    while !ranges.is_empty() {
-        let (mut a, mut b) = ranges.pop().unwrap();
-        let mut i = 0;
-        while i < ranges.len() {
-            let (aa, bb) = ranges[i];
-            // there is no overlap iff (a < b < aa < bb) or (aa < bb < a < b)
-            if !(b < aa || bb < a) {
-                // merge the lists
-                a = a.min(aa);
-                b = b.max(bb);
-                ranges.remove(i);
-                // now we may be overlapping things we weren't before, so reset i :(
-                i = 0;
-            } else {
-                // no overlap, try the next one
-                i += 1;
-            }
-        }
-        new.push((a, b));
-    }
-    new
+/// Everything on a row that's within reach of some sensor.
+fn covered(sensors: &[Sensor], row: i64) -> IntervalSet<i64> {
+    sensors.iter().map(|sensor| sensor.range(row)).collect()
 }
 
-fn solve_1(row: i64) {
-    let sensors = parse_input();
-    let ranges: Vec<(i64, i64)> = sensors
+pub fn solve_1(lines: &[String], row: i64) -> i64 {
+    let sensors = parse_input(lines);
+    let covered = covered(&sensors, row);
+    // the beacons we know about are definitely somewhere a beacon can be
+    let beacons: HashSet<i64> = sensors
         .iter()
-        .map(|sensor| sensor.range(row))
-        .filter(Option::is_some)
-        .map(Option::unwrap)
+        .filter(|s| s.by == row && covered.contains(s.bx))
+        .map(|s| s.bx)
         .collect();
-    let ranges = merge_ranges(&ranges);
-    let range_sum = ranges.iter().map(|(a, b)| b - a).reduce(i64::add).unwrap();
-    println!("{}", range_sum);
+    covered.covered() - beacons.len() as i64
 }
 
-fn solve_2(limit: i64) {
-    let sensors = parse_input();
-    for y in 0..limit {
-        let mut x = 0;
-        let mut ranges: Vec<(i64, i64)> = sensors
-            .iter()
-            .map(|s| s.range(y))
-            .filter(Option::is_some)
-            .map(Option::unwrap)
-            .collect();
-        ranges.sort_by(|(a, _), (b, _)| a.cmp(b));
-        for (a, b) in ranges.iter() {
-            if *a <= x && x <= *b {
-                x = b + 1;
-            }
-        }
-        if x < limit {
-            println!("{}", (x * 4000000) + y);
-            return ();
-        }
-    }
+pub fn solve_2(lines: &[String], limit: i64) -> i64 {
+    let sensors = parse_input(lines);
+    // Rows are checked independently, but we still want the first one with a gap
+    let beacon = (0..=limit).into_par_iter().find_map_first(|y| {
+        let x = *covered(&sensors, y).gaps(0..=limit).next()?.start();
+        Some((x * 4000000) + y)
+    });
+    beacon.expect("The distress beacon is nowhere to be found")
 }
-pub fn solve() {
-    // testing
-    // solve_1(10);
-    solve_1(2000000);
 
-    // testing
-    // solve_2(20);
-    solve_2(4000000);
+#[cfg(test)]
+mod test {
+    use super::{solve_1, solve_2};
+    use crate::advent::read_example;
+
+    #[test]
+    fn test_example() {
+        // the example uses a smaller search area than the real thing
+        let lines = read_example(15);
+        assert_eq!(solve_1(&lines, 10), 26);
+        assert_eq!(solve_2(&lines, 20), 56000011);
+    }
 }
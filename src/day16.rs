@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::ops::Add;
 
-use crate::advent;
+use crate::repl;
+use rayon::prelude::*;
 use regex::Regex;
 
 #[derive(Debug, Clone)]
@@ -17,8 +18,7 @@ impl Room {
     }
 }
 
-fn parse_rooms() -> HashMap<String, Room> {
-    let lines = advent::read_input(16);
+fn parse_rooms(lines: &[String]) -> HashMap<String, Room> {
     let re = Regex::new(
         r"Valve ([A-Z]{2}) has flow rate=(\d+); tunnel[s]? lead[s]? to valve[s]? ([A-Z, ]+)",
     )
@@ -62,25 +62,7 @@ impl Node {
         &charcodes[0] * 128 + &charcodes[1]
     }
     fn calculate_distances(&mut self, rooms: &HashMap<String, Room>) {
-        fn visit(
-            rooms: &HashMap<String, Room>,
-            name: &str,
-            distance: u32,
-            distances: &mut HashMap<String, u32>,
-        ) {
-            let room = rooms.get(name).unwrap();
-            if let Some(previous_distance) = distances.get(name) {
-                if &distance >= previous_distance {
-                    return;
-                }
-            }
-            distances.insert(name.to_string(), distance);
-            for adjacent in room.tunnels.iter() {
-                visit(rooms, adjacent, distance + 1, distances);
-            }
-        }
-        let mut distances = HashMap::<String, u32>::new();
-        visit(rooms, &self.name, 0, &mut distances);
+        let distances = room_distances(rooms, &self.name);
         for (name, room) in rooms.iter() {
             if room.flow > 0 && name != &self.name {
                 self.edges
@@ -90,6 +72,30 @@ impl Node {
     }
 }
 
+/// The shortest walking distance from the named room to every other room
+fn room_distances(rooms: &HashMap<String, Room>, name: &str) -> HashMap<String, u32> {
+    fn visit(
+        rooms: &HashMap<String, Room>,
+        name: &str,
+        distance: u32,
+        distances: &mut HashMap<String, u32>,
+    ) {
+        let room = rooms.get(name).unwrap();
+        if let Some(previous_distance) = distances.get(name) {
+            if &distance >= previous_distance {
+                return;
+            }
+        }
+        distances.insert(name.to_string(), distance);
+        for adjacent in room.tunnels.iter() {
+            visit(rooms, adjacent, distance + 1, distances);
+        }
+    }
+    let mut distances = HashMap::<String, u32>::new();
+    visit(rooms, name, 0, &mut distances);
+    distances
+}
+
 #[derive(Debug, Clone)]
 struct Graph {
     nodes: HashMap<u32, Node>,
@@ -113,7 +119,7 @@ impl Graph {
     }
 }
 
-fn solve_1(graph: &Graph, time_limit: u32) {
+fn best_flow(graph: &Graph, time_limit: u32) -> u32 {
     let mut visited = vec![];
     fn visit(graph: &Graph, id: u32, time: u32, time_limit: u32, visited: &mut Vec<u32>) -> u32 {
         if time > time_limit {
@@ -150,10 +156,7 @@ fn solve_1(graph: &Graph, time_limit: u32) {
         visited.pop();
         max_flow
     }
-    println!(
-        "{}",
-        visit(graph, Node::idcode("AA"), 0, time_limit, &mut visited)
-    );
+    visit(graph, Node::idcode("AA"), 0, time_limit, &mut visited)
 }
 
 fn max_flow(graph: &Graph, time_limit: u32, ban_list: &Vec<u32>) -> u32 {
@@ -206,24 +209,22 @@ fn max_flow(graph: &Graph, time_limit: u32, ban_list: &Vec<u32>) -> u32 {
     visit(&graph, Node::idcode("AA"), 0, time_limit, &mut visited)
 }
 
-fn solve_2(graph: &Graph) {
-    fn test_ban_lists(
-        graph: &Graph,
+fn best_flow_with_elephant(graph: &Graph) -> u32 {
+    // Every way of splitting the valves between us and the elephant
+    fn split_ban_lists(
         a: &mut Vec<u32>,
         b: &mut Vec<u32>,
         nodes: &mut Vec<u32>,
-    ) -> u32 {
+        splits: &mut Vec<(Vec<u32>, Vec<u32>)>,
+    ) {
         if let Some(node_id) = nodes.pop() {
             a.push(node_id);
-            let x = test_ban_lists(graph, a, b, nodes);
+            split_ban_lists(a, b, nodes, splits);
             b.push(a.pop().unwrap());
-            let y = test_ban_lists(graph, a, b, nodes);
+            split_ban_lists(a, b, nodes, splits);
             nodes.push(b.pop().unwrap());
-            x.max(y)
         } else {
-            let x = max_flow(graph, 26, a);
-            let y = max_flow(graph, 26, b);
-            x + y
+            splits.push((a.clone(), b.clone()));
         }
     }
     let aa = Node::idcode("AA");
@@ -235,13 +236,92 @@ fn solve_2(graph: &Graph) {
         .collect();
     let mut a: Vec<u32> = vec![];
     let mut b: Vec<u32> = vec![];
-    println!("{}", test_ban_lists(graph, &mut a, &mut b, &mut node_ids));
+    let mut splits = vec![];
+    split_ban_lists(&mut a, &mut b, &mut node_ids, &mut splits);
+    log::debug!("Trying {} ways to split the valves", splits.len());
+    // Each split is independent, so farm them out
+    splits
+        .par_iter()
+        .map(|(a, b)| max_flow(graph, 26, a) + max_flow(graph, 26, b))
+        .max()
+        .unwrap()
 }
 
-pub fn solve() {
-    let rooms = parse_rooms();
+struct GraphExplorer {
+    rooms: HashMap<String, Room>,
+    graph: Graph,
+}
+
+impl GraphExplorer {
+    fn room(&self, name: Option<&&str>) -> Result<&Room, String> {
+        let name = name.ok_or("missing valve name")?;
+        self.rooms
+            .get(*name)
+            .ok_or(format!("no such valve: {}", name))
+    }
+}
+
+impl repl::Explorer for GraphExplorer {
+    fn help(&self) -> Vec<&'static str> {
+        vec![
+            "dist FROM TO    walking distance between two valves, e.g. `dist AA DD`",
+            "room NAME       flow rate and tunnels of a valve",
+            "edges NAME      distances from a valve to every valve worth opening",
+        ]
+    }
+    fn query(&self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "dist" => {
+                let from = self.room(args.first())?;
+                let to = self.room(args.get(1))?;
+                let distances = room_distances(&self.rooms, &from.name);
+                match distances.get(&to.name) {
+                    Some(distance) => Ok(distance.to_string()),
+                    None => Err(format!("{} is unreachable from {}", to.name, from.name)),
+                }
+            }
+            "room" => {
+                let room = self.room(args.first())?;
+                Ok(format!(
+                    "flow rate {}, tunnels to {}",
+                    room.flow,
+                    room.tunnels.join(", ")
+                ))
+            }
+            "edges" => {
+                let room = self.room(args.first())?;
+                let node = self
+                    .graph
+                    .nodes
+                    .get(&Node::idcode(&room.name))
+                    .ok_or(format!("{} is not in the graph", room.name))?;
+                let mut edges: Vec<(&str, u32)> = node
+                    .edges
+                    .iter()
+                    .map(|(id, distance)| (self.graph.nodes[id].name.as_str(), *distance))
+                    .collect();
+                edges.sort();
+                Ok(edges
+                    .iter()
+                    .map(|(name, distance)| format!("{} {}", name, distance))
+                    .collect::<Vec<String>>()
+                    .join("\n"))
+            }
+            _ => Err(format!("unknown command {}", command)),
+        }
+    }
+}
+
+pub fn explorer(lines: &[String]) -> Box<dyn repl::Explorer> {
+    let rooms = parse_rooms(lines);
     let graph = Graph::new(&rooms);
-    solve_1(&graph, 30);
+    Box::new(GraphExplorer { rooms, graph })
+}
+
+pub fn solve_1(lines: &[String]) -> u32 {
+    best_flow(&Graph::new(&parse_rooms(lines)), 30)
+}
 
-    solve_2(&graph);
+pub fn solve_2(lines: &[String]) -> u32 {
+    best_flow_with_elephant(&Graph::new(&parse_rooms(lines)))
 }
@@ -1,14 +1,11 @@
 use std::collections::VecDeque;
 
-use crate::advent;
-
 
 #[derive(Debug)]
 enum Wind { Left, Right }
 
 
-fn get_wind() -> Vec<Wind> {
-    let lines = advent::read_input(17);
+fn get_wind(lines: &[String]) -> Vec<Wind> {
     let line = lines.get(0).unwrap();
     line.chars().map(|c| match c {
         '<' => Wind::Left,
@@ -132,7 +129,8 @@ impl Cave {
             self.rocks.pop_back();
         }
     }
-    fn _print(&self) {
+    fn render(&self) -> String {
+        let mut out = String::new();
         for y in (self.max_y-10..self.max_y+2).rev() {
             for x in 0..7 {
                 let mut empty = true;
@@ -146,16 +144,17 @@ impl Cave {
                     }
                 }
                 if empty {
-                    print!(".");
+                    out.push('.');
                 } else {
-                    print!("#");
+                    out.push('#');
                 }
             }
-            println!();
+            out.push('\n');
             if y == 9 {
-                println!("XXXXXXX");
+                out.push_str("XXXXXXX\n");
             }
         }
+        out
     }
     fn hash_state(&self) -> i64 {
         let mut s = 0;
@@ -170,8 +169,8 @@ impl Cave {
     }
 }
 
-fn solve_1(limit: i64) -> i64 {
-    let winds = get_wind();
+fn tower_height(lines: &[String], limit: i64) -> i64 {
+    let winds = get_wind(lines);
     let mut winds = winds.iter().cycle();
     let shapes = the_shapes();
     let mut shapes = shapes.iter().cycle();
@@ -191,17 +190,17 @@ fn solve_1(limit: i64) -> i64 {
     cave.max_y + 1
 }
 
-fn solve_2() {
-    let winds = get_wind();
+fn extrapolated_tower_height(lines: &[String], limit: i64) -> i64 {
+    let winds = get_wind(lines);
     let cycle_len = num::integer::lcm(winds.len(), 5);
-    println!("{} {} -> {}", winds.len(), num::integer::lcm(winds.len(), 5), cycle_len);
+    log::debug!("{} winds, {} shapes -> cycle length {}", winds.len(), 5, cycle_len);
     let mut winds = winds.iter().cycle();
     let shapes = the_shapes();
     let mut shapes = shapes.iter().cycle().peekable();
     let mut cave = Cave::new();
     let mut hashes = vec![];
     let mut heights = vec![];
-    println!("Searching for a cycle...");
+    log::debug!("Searching for a cycle...");
     for cycle in 0..1000 {
         for _ in 0..cycle_len {
             let shape = shapes.next().unwrap();
@@ -215,20 +214,41 @@ fn solve_2() {
                 }
             }
         }
-        println!("{}", cycle);
-        cave._print();
+        log::debug!("cycle {}", cycle);
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!("cave after cycle {}:\n{}", cycle, cave.render());
+        }
         let hash = cave.hash_state();
         if hashes.contains(&hash) {
             let initial_cycle = hashes.iter().position(|&h| h == hash).unwrap();
-            println!("Found it! {} {}", cycle, initial_cycle);
+            log::debug!("Cycle {} repeats cycle {}", cycle, initial_cycle);
             let initial_height = heights.get(initial_cycle).unwrap();
             let initial_rocks = cycle_len * (initial_cycle + 1);
             let final_height = cave.max_y + 1;
             let final_rocks = cycle_len * (cycle + 1);
             let cycle_height = final_height - initial_height;
             let cycle_rocks = final_rocks - initial_rocks;
-            println!("{} {}", cycle_rocks, cycle_height);
-            return;
+            log::debug!("Every {} rocks the tower grows by {}", cycle_rocks, cycle_height);
+            if limit < final_rocks as i64 {
+                // the tower is already past the limit, so there's nothing to skip
+                return tower_height(lines, limit);
+            }
+            // Skip as many whole cycles as we can, then drop the leftover rocks one by one
+            let remaining_rocks = limit - final_rocks as i64;
+            let skipped_cycles = remaining_rocks / cycle_rocks as i64;
+            for _ in 0..(remaining_rocks % cycle_rocks as i64) {
+                let shape = shapes.next().unwrap();
+                let mut rock = Rock::new(shape, &cave);
+                loop {
+                    let wind = winds.next().unwrap();
+                    rock.blow(wind, &cave);
+                    if rock.lower(&cave) {
+                        cave.settle(rock);
+                        break;
+                    }
+                }
+            }
+            return cave.max_y + 1 + (skipped_cycles * cycle_height);
         }
         hashes.push(hash);
         heights.push(cave.max_y + 1);
@@ -237,7 +257,30 @@ fn solve_2() {
 }
 
 
-pub fn solve() {
-    println!("{}", solve_1(2022));
-    solve_2();
+pub fn solve_1(lines: &[String]) -> i64 {
+    tower_height(lines, 2022)
+}
+
+pub fn solve_2(lines: &[String]) -> i64 {
+    extrapolated_tower_height(lines, 1000000000000)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{extrapolated_tower_height, tower_height};
+    use crate::advent::read_example;
+
+    #[test]
+    fn test_extrapolation() {
+        // skipping cycles gets the same height as dropping every rock
+        let lines = read_example(17);
+        for limit in [1, 100, 319, 2022, 3333, 5000, 7919] {
+            assert_eq!(
+                extrapolated_tower_height(&lines, limit),
+                tower_height(&lines, limit),
+                "{} rocks",
+                limit
+            );
+        }
+    }
 }
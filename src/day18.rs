@@ -1,13 +1,10 @@
 use std::collections::HashSet;
 use std::ops::Add;
 
-use crate::advent;
-
 type Pos = (i32, i32, i32);
 type Volume = HashSet<Pos>;
 
-fn read_input() -> Volume {
-    let lines = advent::read_input(18);
+fn read_input(lines: &[String]) -> Volume {
     lines
         .iter()
         .map(|line| {
@@ -44,8 +41,8 @@ fn surface_area(voxels: &Volume) -> u32 {
         })
         .fold(0, u32::add)
 }
-pub fn solve_1() -> u32 {
-    let voxels = read_input();
+pub fn solve_1(lines: &[String]) -> u32 {
+    let voxels = read_input(lines);
     surface_area(&voxels)
 }
 
@@ -83,16 +80,11 @@ fn find_subset(volume: &Volume, start: &Pos, subset: &mut Volume) {
     }
 }
 
-fn solve_2() -> u32 {
-    let voxels = read_input();
+pub fn solve_2(lines: &[String]) -> u32 {
+    let voxels = read_input(lines);
     let inversion = invert(&voxels);
     let mut outside = HashSet::new();
     find_subset(&inversion, &(0, 0, 0), &mut outside);
     let filled_voxels = invert(&outside);
     surface_area(&filled_voxels)
 }
-
-pub fn solve() {
-    println!("{}", solve_1());
-    println!("{}", solve_2());
-}
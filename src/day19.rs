@@ -1,8 +1,7 @@
+use rayon::prelude::*;
 use regex::Regex;
 use std::ops::Mul;
 
-use crate::advent;
-
 #[derive(Debug, PartialEq, Eq)]
 enum Material {
     Ore,
@@ -20,8 +19,7 @@ struct Blueprint {
     geode: (u32, u32),
 }
 
-fn read_blueprints() -> Vec<Blueprint> {
-    let lines = advent::read_input(19);
+fn read_blueprints(lines: &[String]) -> Vec<Blueprint> {
     let re = Regex::new(r"Blueprint (\d+).*(\d+) ore.*(\d+) ore.*(\d+) ore and (\d+) clay.* (\d+) ore and (\d+) obsidian").unwrap();
     lines
         .iter()
@@ -186,30 +184,21 @@ fn find_max_geodes(blueprint: &Blueprint, depth: u32) -> u32 {
     ctx.recurse(&State::new());
     ctx.max_geodes
 }
-fn solve_1() -> u32 {
-    let blueprints = read_blueprints();
-    let mut total_quality = 0;
-    for blueprint in blueprints.iter() {
-        let geodes = find_max_geodes(blueprint, 24);
-        total_quality += blueprint.id * geodes;
-    }
-    total_quality
-}
-
-fn solve_2() -> u32 {
-    let blueprints = read_blueprints();
+// runs in 81 seconds
+// also it's wrong :((((
+pub fn solve_1(lines: &[String]) -> u32 {
+    let blueprints = read_blueprints(lines);
+    // Blueprints don't affect each other, so search them all at once
     blueprints
-        .iter()
-        .take(3)
-        .map(|blueprint| {
-            let g = find_max_geodes(blueprint, 32);
-            g
-        })
-        .fold(1, u32::mul)
+        .par_iter()
+        .map(|blueprint| blueprint.id * find_max_geodes(blueprint, 24))
+        .sum()
 }
-pub fn solve() {
-    // runs in 81 seconds
-    // also it's wrong :((((
-    println!("{}", solve_1());
-    println!("{}", solve_2());
+
+pub fn solve_2(lines: &[String]) -> u32 {
+    let blueprints = read_blueprints(lines);
+    blueprints[..blueprints.len().min(3)]
+        .par_iter()
+        .map(|blueprint| find_max_geodes(blueprint, 32))
+        .reduce(|| 1, u32::mul)
 }
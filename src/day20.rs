@@ -1,9 +1,10 @@
-use core::fmt;
+#![cfg_attr(not(feature = "bigint"), allow(clippy::clone_on_copy))]
 
-use crate::advent;
+use crate::bigint::{self, Signed};
+use core::fmt;
 
 struct LList {
-    nodes: Vec<i64>,
+    nodes: Vec<Signed>,
     links: Vec<(usize, usize)>,
 }
 
@@ -14,7 +15,7 @@ impl LList {
             links: Vec::new(),
         }
     }
-    fn push(&mut self, value: i64) {
+    fn push(&mut self, value: Signed) {
         self.nodes.push(value);
         let len = self.nodes.len();
         if self.links.len() == 0 {
@@ -35,11 +36,9 @@ impl LList {
         }
     }
     fn apply_swap(&mut self, start: usize) {
-        let distance = self.nodes[start];
-        let distance = distance + 10 * 811589153 * (self.nodes.len() as i64 - 1);
-        let distance = distance % (self.nodes.len() as i64 - 1);
-        let distance = distance + 1;
-        let mut distance = distance as u64;
+        // moving past every other node gets you back where you started
+        let distance = bigint::rem_euclid(&self.nodes[start], self.nodes.len() as u32 - 1);
+        let mut distance = distance + 1;
         let mut end = start;
         while distance > 0 {
             (_, end) = self.links[end];
@@ -76,16 +75,25 @@ impl fmt::Display for LList {
     }
 }
 
-fn build_list() -> LList {
-    let lines = advent::read_input(20);
+impl From<&Vec<i32>> for LList {
+    fn from(values: &Vec<i32>) -> Self {
+        let mut list = LList::new();
+        for value in values.iter() {
+            list.push(Signed::from(*value));
+        }
+        list
+    }
+}
+
+fn build_list(lines: &[String]) -> LList {
     let mut list = LList::new();
     for line in lines {
         list.push(line.parse().unwrap());
     }
     list
 }
-pub fn solve_1() -> i64 {
-    let mut list = build_list();
+pub fn solve_1(lines: &[String]) -> Signed {
+    let mut list = build_list(lines);
     for i in 0..list.nodes.len() {
         list.apply_swap(i);
     }
@@ -93,18 +101,18 @@ pub fn solve_1() -> i64 {
     while list.nodes[index] != 0 {
         (_, index) = list.links[index];
     }
-    let mut sum = 0;
+    let mut sum = Signed::from(0);
     for _ in 0..3 {
         for _ in 0..1000 {
             (_, index) = list.links[index];
         }
-        sum += list.nodes[index];
+        sum += &list.nodes[index];
     }
     sum
 }
-pub fn solve_2() -> i64 {
-    let mut list = build_list();
-    list.nodes = list.nodes.iter().map(|v| v * 811589153).collect();
+pub fn solve_2(lines: &[String]) -> Signed {
+    let mut list = build_list(lines);
+    list.nodes = list.nodes.iter().map(|v| v.clone() * 811589153).collect();
     for _ in 0..10 {
         for i in 0..list.nodes.len() {
             list.apply_swap(i);
@@ -114,19 +122,15 @@ pub fn solve_2() -> i64 {
     while list.nodes[index] != 0 {
         (_, index) = list.links[index];
     }
-    let mut sum = 0;
+    let mut sum = Signed::from(0);
     for _ in 0..3 {
         for _ in 0..1000 {
             (_, index) = list.links[index];
         }
-        sum += list.nodes[index];
+        sum += &list.nodes[index];
     }
     sum
 }
-pub fn solve() {
-    println!("{}", solve_1());
-    println!("{}", solve_2());
-}
 
 #[cfg(test)]
 mod test {
@@ -1,6 +1,9 @@
+#![cfg_attr(not(feature = "bigint"), allow(clippy::clone_on_copy))]
+
 use std::collections::HashMap;
 
-use crate::advent;
+use crate::bigint::Signed;
+use crate::repl;
 
 #[derive(Debug)]
 enum Op {
@@ -10,7 +13,7 @@ enum Op {
     Divide,
 }
 impl Op {
-    fn apply(&self, a: i64, b: i64) -> i64 {
+    fn apply(&self, a: Signed, b: Signed) -> Signed {
         match self {
             Op::Add => a + b,
             Op::Subtract => a - b,
@@ -18,6 +21,14 @@ impl Op {
             Op::Divide => a / b,
         }
     }
+    fn symbol(&self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Subtract => "-",
+            Op::Multiply => "*",
+            Op::Divide => "/",
+        }
+    }
 }
 impl From<&str> for Op {
     fn from(op: &str) -> Self {
@@ -33,19 +44,18 @@ impl From<&str> for Op {
 
 #[derive(Debug)]
 enum Monkey {
-    Number(i64),
+    Number(Signed),
     Operation(Op, String, String),
 }
 
-fn read_monkeys() -> HashMap<String, Monkey> {
-    let lines = advent::read_input(21);
+fn read_monkeys(lines: &[String]) -> HashMap<String, Monkey> {
     let mut monkeys = HashMap::new();
     lines.iter().for_each(|line| {
         let mut s = line.split(": ");
         let name = s.next().unwrap().to_string();
         let remainder = s.next().unwrap();
         let monkey: Monkey;
-        if let Ok(x) = remainder.parse::<i64>() {
+        if let Ok(x) = remainder.parse::<Signed>() {
             monkey = Monkey::Number(x)
         } else {
             let mut ss = remainder.split(" ");
@@ -59,32 +69,32 @@ fn read_monkeys() -> HashMap<String, Monkey> {
     monkeys
 }
 
-fn eval(monkeys: &HashMap<String, Monkey>, monkey: &str) -> i64 {
+fn eval(monkeys: &HashMap<String, Monkey>, monkey: &str) -> Signed {
     let monkey = monkeys.get(monkey).unwrap();
     match monkey {
-        Monkey::Number(i) => *i,
+        Monkey::Number(i) => i.clone(),
         Monkey::Operation(op, left, right) => op.apply(eval(monkeys, left), eval(monkeys, right)),
     }
 }
 
-fn solve_1() -> i64 {
-    let monkeys = read_monkeys();
+pub fn solve_1(lines: &[String]) -> Signed {
+    let monkeys = read_monkeys(lines);
     eval(&monkeys, "root")
 }
 // Evaluate as normal, but error out if we encounter humn
-fn eval_2(monkeys: &HashMap<String, Monkey>, monkey: &str) -> Result<i64, ()> {
+fn eval_2(monkeys: &HashMap<String, Monkey>, monkey: &str) -> Result<Signed, ()> {
     if monkey == "humn" {
         return Err(());
     }
     let monkey = monkeys.get(monkey).unwrap();
     Ok(match monkey {
-        Monkey::Number(i) => *i,
+        Monkey::Number(i) => i.clone(),
         Monkey::Operation(op, left, right) => {
             op.apply(eval_2(monkeys, left)?, eval_2(monkeys, right)?)
         }
     })
 }
-fn derive(monkeys: &HashMap<String, Monkey>, monkey_name: &str, target: i64) -> i64 {
+fn derive(monkeys: &HashMap<String, Monkey>, monkey_name: &str, target: Signed) -> Signed {
     if monkey_name == "humn" {
         return target;
     }
@@ -117,8 +127,8 @@ fn derive(monkeys: &HashMap<String, Monkey>, monkey_name: &str, target: i64) ->
         panic!("Trying to derive a constant, non-humn monkey")
     }
 }
-fn solve_2() -> i64 {
-    let monkeys = read_monkeys();
+pub fn solve_2(lines: &[String]) -> Signed {
+    let monkeys = read_monkeys(lines);
     let root = monkeys.get("root").unwrap();
     if let Monkey::Operation(_, left, right) = root {
         let left_val = eval_2(&monkeys, left);
@@ -137,7 +147,44 @@ fn solve_2() -> i64 {
         panic!()
     }
 }
-pub fn solve() {
-    println!("{}", solve_1());
-    println!("{}", solve_2());
+struct MonkeyExplorer {
+    monkeys: HashMap<String, Monkey>,
+}
+
+impl MonkeyExplorer {
+    fn monkey<'a>(&self, name: Option<&&'a str>) -> Result<&'a str, String> {
+        let name = name.ok_or("missing monkey name")?;
+        if self.monkeys.contains_key(*name) {
+            Ok(name)
+        } else {
+            Err(format!("no such monkey: {}", name))
+        }
+    }
+}
+
+impl repl::Explorer for MonkeyExplorer {
+    fn help(&self) -> Vec<&'static str> {
+        vec![
+            "eval NAME    the number a monkey yells, e.g. `eval root`",
+            "show NAME    what a monkey is waiting on",
+        ]
+    }
+    fn query(&self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "eval" => Ok(eval(&self.monkeys, self.monkey(args.first())?).to_string()),
+            "show" => Ok(match &self.monkeys[self.monkey(args.first())?] {
+                Monkey::Number(i) => i.to_string(),
+                Monkey::Operation(op, left, right) => {
+                    format!("{} {} {}", left, op.symbol(), right)
+                }
+            }),
+            _ => Err(format!("unknown command {}", command)),
+        }
+    }
+}
+
+pub fn explorer(lines: &[String]) -> Box<dyn repl::Explorer> {
+    Box::new(MonkeyExplorer {
+        monkeys: read_monkeys(lines),
+    })
 }
@@ -1,5 +1,3 @@
-use crate::advent;
-
 struct Map {
     rows: Vec<Vec<char>>,
     width: i32,
@@ -106,14 +104,13 @@ impl Direction {
     }
 }
 
-fn solve_1() -> i32 {
-    let mut lines = advent::read_input(22);
+pub fn solve_1(lines: &[String]) -> i32 {
     let direction_str = lines.last().unwrap();
     let direction_str = direction_str.replace("L", " L ");
     let direction_str = direction_str.replace("R", " R ");
     let directions = direction_str.split(" ");
-    lines.pop();
-    lines.pop();
+    // the map is everything above the blank line
+    let lines = lines[..lines.len() - 2].to_vec();
     let map = Map::new(lines);
     let mut x = map.rows[0]
         .iter()
@@ -212,14 +209,13 @@ impl Cube {
         }
     }
 }
-fn solve_2() -> i32 {
-    let mut lines = advent::read_input(22);
+pub fn solve_2(lines: &[String]) -> i32 {
     let direction_str = lines.last().unwrap();
     let direction_str = direction_str.replace("L", " L ");
     let direction_str = direction_str.replace("R", " R ");
     let directions = direction_str.split(" ");
-    lines.pop();
-    lines.pop();
+    // the map is everything above the blank line
+    let lines = lines[..lines.len() - 2].to_vec();
     let cube = Cube::new(lines);
     let mut x = cube.map.rows[0]
         .iter()
@@ -241,7 +237,3 @@ fn solve_2() -> i32 {
     }
     (1000 * (y + 1)) + (4 * (x + 1)) + dir.num()
 }
-pub fn solve() {
-    println!("{}", solve_1());
-    println!("{}", solve_2());
-}
@@ -1,7 +1,5 @@
 use std::collections::HashMap;
 
-use crate::advent;
-
 type Pos = (i64, i64);
 
 #[derive(Clone, Debug)]
@@ -158,46 +156,62 @@ impl Field {
             *self.elves.iter().map(|((_, y), _)| y).max().unwrap(),
         )
     }
-    #[allow(dead_code)]
-    fn print(&self) {
+    fn render(&self) -> String {
         let (minx, maxx, miny, maxy) = self.bounds();
-        for y in miny..maxy + 1 {
-            for x in minx..maxx + 1 {
-                if self.elves.contains_key(&(x, y)) {
-                    print!("#");
-                } else {
-                    print!(".");
-                }
-            }
-            println!();
-        }
+        (miny..maxy + 1)
+            .map(|y| {
+                (minx..maxx + 1)
+                    .map(|x| {
+                        if self.elves.contains_key(&(x, y)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
 
-fn solve_1() -> i64 {
-    let lines = advent::read_input(23);
-    let mut field = Field::new(lines);
-    //field.print();
+pub fn solve_1(lines: &[String]) -> i64 {
+    let mut field = Field::new(lines.to_vec());
     for i in 0..10 {
         field.round(i % 4);
     }
-    // field.print();
+    log::debug!("After 10 rounds:\n{}", field.render());
     let (minx, maxx, miny, maxy) = field.bounds();
     ((maxx + 1 - minx) * (maxy + 1 - miny)) - (field.elves.len() as i64)
 }
-fn solve_2() -> usize {
-    let lines = advent::read_input(23);
-    let mut field = Field::new(lines);
-    //field.print();
+pub fn solve_2(lines: &[String]) -> usize {
+    let mut field = Field::new(lines.to_vec());
     let mut i = 0;
     while field.round(i % 4) {
         i += 1;
     }
-    // field.print();
+    log::debug!("Settled after {} rounds:\n{}", i + 1, field.render());
     i + 1
 }
 
-pub fn solve() {
-    println!("{}", solve_1());
-    println!("{}", solve_2());
+#[cfg(test)]
+mod test {
+    use super::Field;
+    use crate::advent::read_example;
+    use crate::snapshot::assert_snapshot;
+
+    #[test]
+    fn test_render() {
+        let mut field = Field::new(read_example(23));
+        assert_snapshot("day23_start", &field.render());
+        for i in 0..10 {
+            field.round(i % 4);
+        }
+        assert_snapshot("day23_round_10", &field.render());
+        let mut i = 10;
+        while field.round(i % 4) {
+            i += 1;
+        }
+        assert_snapshot("day23_settled", &field.render());
+    }
 }
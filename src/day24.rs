@@ -1,7 +1,5 @@
 use std::collections::HashSet;
 
-use crate::advent;
-
 type Pos = (usize, usize);
 
 #[derive(Clone, Debug)]
@@ -128,16 +126,14 @@ fn traverse(valley: &mut Valley, start: &Pos, end: &Pos) -> u32 {
     minutes
 }
 
-fn solve_1() -> u32 {
-    let lines = advent::read_input(24);
-    let mut valley = Valley::new(lines);
+pub fn solve_1(lines: &[String]) -> u32 {
+    let mut valley = Valley::new(lines.to_vec());
     let width = valley.width - 1;
     let height = valley.height - 1;
     traverse(&mut valley, &(0, 0), &(width, height))
 }
-fn solve_2() -> u32 {
-    let lines = advent::read_input(24);
-    let mut valley = Valley::new(lines);
+pub fn solve_2(lines: &[String]) -> u32 {
+    let mut valley = Valley::new(lines.to_vec());
     let width = valley.width - 1;
     let height = valley.height - 1;
     traverse(&mut valley, &(0, 0), &(width, height))
@@ -145,9 +141,8 @@ fn solve_2() -> u32 {
         + traverse(&mut valley, &(0, 0), &(width, height))
 }
 /// https://old.reddit.com/r/adventofcode/comments/zu4uee/2022_day_24_part_3_can_you_solve_this_harder/
-fn solve_3() -> u64 {
-    let lines = advent::read_input(24);
-    let mut valley = Valley::new(lines);
+pub fn solve_3(lines: &[String]) -> u64 {
+    let mut valley = Valley::new(lines.to_vec());
     let width = valley.width - 1;
     let height = valley.height - 1;
     let mut minutes = traverse(&mut valley, &(0, 0), &(width, height)) as u64;
@@ -157,8 +152,3 @@ fn solve_3() -> u64 {
     }
     minutes
 }
-pub fn solve() {
-    println!("{}", solve_1());
-    println!("{}", solve_2());
-    println!("{}", solve_3());
-}
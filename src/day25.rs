@@ -1,72 +1,65 @@
-use crate::advent;
+use crate::bigint::{self, Signed};
 
 type SNAFU = String;
 
-fn to_int(snafu: &SNAFU) -> i64 {
-    let mut total = 0;
-    let mut place = 1;
-    for c in snafu.chars().rev() {
-        total += match c {
-            '2' => place * 2,
-            '1' => place,
+fn to_int(snafu: &SNAFU) -> Signed {
+    snafu.chars().fold(Signed::from(0), |total, c| {
+        let digit = match c {
+            '2' => 2,
+            '1' => 1,
             '0' => 0,
-            '-' => -place,
-            '=' => -(place * 2),
-            _ => panic!(),
+            '-' => -1,
+            '=' => -2,
+            _ => panic!("not a SNAFU digit: {}", c),
         };
-        place *= 5;
-    }
-    // let p = (i as f64).log(5) as i64;
-    total
+        total * 5 + Signed::from(digit)
+    })
 }
 
-fn to_snafu(mut i: i64) -> SNAFU {
+fn to_snafu(mut i: Signed) -> SNAFU {
     if i == 0 {
         return "0".to_string();
     }
-    let exp = (i as f64).log(5.0) as u32;
-    let mut place = (5 as i64).pow(exp);
-    let mut snafu = "".to_string();
-    while place > 1 {
-        let c;
-        if i > place + (place / 2) {
-            c = '2';
-            i -= place * 2;
-        } else if i > (place / 2) {
-            c = '1';
-            i -= place;
-        } else if i > -(place / 2) {
-            c = '0';
-        } else if i > -place - (place / 2) {
-            c = '-';
-            i += place;
-        } else {
-            c = '=';
-            i += place * 2;
-        }
+    let mut snafu = vec![];
+    while i != 0 {
+        // it's base 5, except 3 and 4 are written as -2 and -1 with one carried over
+        let (c, digit) = match bigint::rem_euclid(&i, 5) {
+            0 => ('0', 0),
+            1 => ('1', 1),
+            2 => ('2', 2),
+            3 => ('=', -2),
+            _ => ('-', -1),
+        };
         snafu.push(c);
-        place /= 5;
+        i = (i - Signed::from(digit)) / 5;
     }
-    if i == 2 {
-        snafu.push('2');
-    } else if i == 1 {
-        snafu.push('1');
-    } else if i == 0 {
-        snafu.push('0');
-    } else if i == -1 {
-        snafu.push('-');
-    } else {
-        snafu.push('=');
-    }
-
-    snafu
+    snafu.iter().rev().collect()
 }
 
-fn solve_1() -> SNAFU {
-    let lines = advent::read_input(25);
-    let sum = lines.iter().map(to_int).fold(0, |a, b| a + b);
-    to_snafu(sum)
+pub fn solve_1(lines: &[String]) -> SNAFU {
+    to_snafu(lines.iter().map(to_int).sum())
 }
-pub fn solve() {
-    println!("{}", solve_1());
+
+#[cfg(test)]
+mod test {
+    use super::{solve_1, to_int, to_snafu};
+    use crate::advent::read_example;
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve_1(&read_example(25)), "2=-1=0");
+        for snafu in ["0", "1", "2=", "1=-0-2", "1121-1110-1=0"] {
+            assert_eq!(to_snafu(to_int(&snafu.to_string())), snafu);
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_beyond_64_bits() {
+        // 5^40 is about 9 * 10^27
+        let huge = format!("1{}", "=-0-2".repeat(8));
+        let lines = vec![huge.clone(), huge.clone(), "1".to_string()];
+        assert_eq!(to_int(&huge).to_string(), "5083155407476097001919520497");
+        assert_eq!(solve_1(&lines), "11=0-=1=0-=1=0-=1=0-=1=0-=1=0-=1=0-=1=0-0");
+    }
 }
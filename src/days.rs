@@ -0,0 +1,79 @@
+use crate::advent::{Answer, Part};
+use crate::*;
+
+pub struct Day {
+    pub day: i32,
+    pub parts: Vec<Part>,
+}
+
+macro_rules! day {
+    ($day:expr, $($part:expr),+) => {
+        Day {
+            day: $day,
+            parts: vec![$(|lines: &[String]| Answer::from($part(lines))),+],
+        }
+    };
+}
+
+pub fn all() -> Vec<Day> {
+    vec![
+        day!(1, day01::solve_1, day01::solve_2),
+        day!(2, day02::solve_1, day02::solve_2),
+        day!(3, day03::solve_1, day03::solve_2),
+        day!(4, day04::solve_1, day04::solve_2),
+        day!(5, day05::solve_1, day05::solve_2),
+        day!(6, day06::solve_1, day06::solve_2),
+        day!(7, day07::solve_1, day07::solve_2),
+        day!(8, day08::solve_1, day08::solve_2),
+        day!(9, day09::solve_1, day09::solve_2),
+        day!(10, day10::solve_1, day10::solve_2),
+        day!(11, day11::solve_1, day11::solve_2),
+        day!(12, day12::solve_1, day12::solve_2),
+        day!(13, day13::solve_1, day13::solve_2),
+        day!(14, day14::solve_1, day14::solve_2),
+        day!(15, |lines| day15::solve_1(lines, 2000000), |lines| {
+            day15::solve_2(lines, 4000000)
+        }),
+        day!(16, day16::solve_1, day16::solve_2),
+        day!(17, day17::solve_1, day17::solve_2),
+        day!(18, day18::solve_1, day18::solve_2),
+        day!(19, day19::solve_1, day19::solve_2),
+        day!(20, day20::solve_1, day20::solve_2),
+        day!(21, day21::solve_1, day21::solve_2),
+        day!(22, day22::solve_1, day22::solve_2),
+        day!(23, day23::solve_1, day23::solve_2),
+        day!(24, day24::solve_1, day24::solve_2, day24::solve_3),
+        day!(25, day25::solve_1),
+    ]
+}
+
+pub fn get(day: i32) -> Option<Day> {
+    all().into_iter().find(|d| d.day == day)
+}
+
+/// Solve one part of a day for an input that's already in memory, e.g. pasted into a web page.
+pub fn solve(day: i32, part: usize, input: &str) -> Result<Answer, String> {
+    let day = get(day).ok_or(format!("Day {} isn't solved yet", day))?;
+    let solve = part
+        .checked_sub(1)
+        .and_then(|i| day.parts.get(i))
+        .ok_or(format!("Day {} doesn't have a part {}", day.day, part))?;
+    let lines: Vec<String> = input.lines().map(str::to_string).collect();
+    Ok(solve(&lines))
+}
+
+#[cfg(test)]
+mod test {
+    use super::solve;
+    use crate::advent::Answer;
+
+    #[test]
+    fn test_solve() {
+        let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+        assert_eq!(solve(1, 1, input), Ok(Answer::Number(24000)));
+        assert_eq!(solve(1, 2, input), Ok(Answer::Number(45000)));
+        assert!(solve(1, 3, input).is_err());
+        assert!(solve(1, 0, input).is_err());
+        assert!(solve(26, 1, input).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::inputs;
+use crate::runner::PartResult;
+
+/// One solved part, as appended to the history file after every `run`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// Seconds since the epoch
+    pub time: u64,
+    /// What was checked out, as `git describe` has it, e.g. `3e1e561-dirty`
+    pub commit: String,
+    pub day: i32,
+    pub part: usize,
+    /// Which input the part was solved for, since answers only compare on the same one
+    pub input: String,
+    pub answer: String,
+    pub seconds: f64,
+}
+
+fn current_commit() -> String {
+    Command::new("git")
+        .args(["describe", "--always", "--dirty", "--abbrev=7"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Append the results of a run to the history file.
+pub fn record(
+    config: &Config,
+    results: &[PartResult],
+    input: &Option<String>,
+) -> Result<(), String> {
+    let commit = current_commit();
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let mut lines = String::new();
+    for result in results {
+        let entry = Entry {
+            time,
+            commit: commit.clone(),
+            day: result.day,
+            part: result.part,
+            input: match input {
+                Some(path) => path.clone(),
+                None => config.input_file_path(result.day).display().to_string(),
+            },
+            answer: result.answer.to_string(),
+            seconds: result.duration.as_secs_f64(),
+        };
+        lines += &serde_json::to_string(&entry).unwrap();
+        lines.push('\n');
+    }
+    let path = &config.history_file;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| f.write_all(lines.as_bytes()))
+        .map_err(|e| format!("Can't write to {}: {}", path.display(), e))
+}
+
+fn parse_entry(line: &str) -> Result<Entry, String> {
+    let entry: Entry = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    // the file can be edited by hand, and a time that isn't one can't be shown
+    if Duration::try_from_secs_f64(entry.seconds).is_err() {
+        return Err(format!("{} isn't a number of seconds", entry.seconds));
+    }
+    Ok(entry)
+}
+
+fn read(config: &Config) -> Result<Vec<Entry>, String> {
+    let path = &config.history_file;
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("No history in {}: {}", path.display(), e))?;
+    let mut entries = vec![];
+    for (number, line) in text.lines().enumerate() {
+        match parse_entry(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => log::warn!("Skipping line {} of {}: {}", number + 1, path.display(), e),
+        }
+    }
+    Ok(entries)
+}
+
+fn format_time(time: u64) -> String {
+    let (year, month, day) = inputs::civil_from_days(time as i64 / 86400);
+    let seconds = time % 86400;
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60
+    )
+}
+
+/// The runs of one part on one input, oldest first, with answer changes flagged and a summary
+/// of how the timings went.
+fn render_runs(runs: &[&Entry]) -> String {
+    let Some(latest) = runs.last() else {
+        return String::new();
+    };
+    let mut lines = vec![format!(
+        "day {} part {} ({}), {} run(s)",
+        latest.day,
+        latest.part,
+        latest.input,
+        runs.len()
+    )];
+    let width = runs.iter().map(|run| run.commit.len()).max().unwrap();
+    let mut changes = 0;
+    for (i, run) in runs.iter().enumerate() {
+        let mut line = format!(
+            "  {}  {:<width$}  {:>9}  {}",
+            format_time(run.time),
+            run.commit,
+            format!("{:.1?}", Duration::from_secs_f64(run.seconds)),
+            run.answer.replace('\n', "\\n"),
+            width = width
+        );
+        if i > 0 && runs[i - 1].answer != run.answer {
+            changes += 1;
+            line += "  <- answer changed";
+        }
+        lines.push(line);
+    }
+    let seconds: Vec<f64> = runs.iter().map(|run| run.seconds).collect();
+    let best = seconds.iter().cloned().fold(f64::INFINITY, f64::min);
+    let mean = seconds.iter().sum::<f64>() / seconds.len() as f64;
+    let mut summary = format!(
+        "  latest {:.1?}, best {:.1?}, mean {:.1?}",
+        Duration::from_secs_f64(latest.seconds),
+        Duration::from_secs_f64(best),
+        Duration::from_secs_f64(mean)
+    );
+    // a run too quick to time can't be compared to
+    if let Some(previous) = runs
+        .len()
+        .checked_sub(2)
+        .map(|i| runs[i])
+        .filter(|previous| previous.seconds > 0.0)
+    {
+        let change = (latest.seconds - previous.seconds) / previous.seconds * 100.0;
+        let direction = if change > 0.0 { "slower" } else { "faster" };
+        summary += &format!(", {:.0}% {} than the run before", change.abs(), direction);
+    }
+    if changes > 0 {
+        summary += &format!(", answer changed {} time(s)", changes);
+    }
+    lines.push(summary);
+    lines.join("\n")
+}
+
+/// Show the recorded runs of a day (or every day), `limit` runs per part at most.
+pub fn show(
+    config: &Config,
+    day: Option<i32>,
+    part: Option<u8>,
+    limit: usize,
+) -> Result<(), String> {
+    let entries = read(config)?;
+    let mut groups: Vec<(i32, usize, &str)> = entries
+        .iter()
+        .filter(|e| day.is_none_or(|day| e.day == day))
+        .filter(|e| part.is_none_or(|part| e.part == part as usize))
+        .map(|e| (e.day, e.part, e.input.as_str()))
+        .collect();
+    groups.sort();
+    groups.dedup();
+    if groups.is_empty() {
+        return Err("No runs recorded for that yet".to_string());
+    }
+    let reports: Vec<String> = groups
+        .into_iter()
+        .map(|(day, part, input)| {
+            let runs: Vec<&Entry> = entries
+                .iter()
+                .filter(|e| e.day == day && e.part == part && e.input == input)
+                .collect();
+            render_runs(&runs[runs.len().saturating_sub(limit)..])
+        })
+        .collect();
+    println!("{}", reports.join("\n\n"));
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_time, parse_entry, render_runs, Entry};
+
+    #[test]
+    fn test_render_runs() {
+        let run = |time, commit: &str, answer: &str, seconds| Entry {
+            time,
+            commit: commit.to_string(),
+            day: 1,
+            part: 2,
+            input: "inputs/01.txt".to_string(),
+            answer: answer.to_string(),
+            seconds,
+        };
+        let runs = [
+            run(1671530580, "3e1e561", "45000", 0.002),
+            run(1671617400, "b23958c-dirty", "45000", 0.001),
+            run(1671617700, "b23958c-dirty", "45001", 0.0015),
+        ];
+        assert_eq!(format_time(1671530580), "2022-12-20 10:03");
+        assert_eq!(
+            render_runs(&runs.iter().collect::<Vec<_>>()),
+            [
+                "day 1 part 2 (inputs/01.txt), 3 run(s)",
+                "  2022-12-20 10:03  3e1e561            2.0ms  45000",
+                "  2022-12-21 10:10  b23958c-dirty      1.0ms  45000",
+                "  2022-12-21 10:15  b23958c-dirty      1.5ms  45001  <- answer changed",
+                "  latest 1.5ms, best 1.0ms, mean 1.5ms, 50% slower than the run before, answer changed 1 time(s)",
+            ]
+            .join("\n")
+        );
+        assert_eq!(render_runs(&[]), "");
+
+        let instant = [run(1671617400, "b23958c", "45000", 0.0), runs[0].clone()];
+        assert_eq!(
+            render_runs(&instant.iter().collect::<Vec<_>>())
+                .lines()
+                .last()
+                .unwrap(),
+            "  latest 2.0ms, best 0.0ns, mean 1.0ms"
+        );
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let line = |seconds: &str| {
+            format!(
+                r#"{{"time":1671530580,"commit":"3e1e561","day":1,"part":2,"input":"inputs/01.txt","answer":"45000","seconds":{}}}"#,
+                seconds
+            )
+        };
+        assert_eq!(parse_entry(&line("0.002")).unwrap().seconds, 0.002);
+        assert_eq!(parse_entry(&line("0")).unwrap().seconds, 0.0);
+        assert!(parse_entry(&line("-0.5")).is_err());
+        assert!(parse_entry(&line("1e300")).is_err());
+        assert!(parse_entry("not json").is_err());
+    }
+}
@@ -0,0 +1,321 @@
+use std::{
+    sync::{Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use reqwest::{
+    blocking::{RequestBuilder, Response},
+    header, StatusCode,
+};
+
+use crate::config::Config;
+
+/// adventofcode.com asks automated tools to identify themselves and say who to contact.
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Talks to adventofcode.com without being a nuisance: every request says who it's from,
+/// requests are spaced out, and hiccups on their end are retried after a pause.
+pub struct Client {
+    inner: reqwest::blocking::Client,
+    min_interval: Duration,
+    retries: u32,
+    backoff: Duration,
+    last_request: Mutex<Option<Instant>>,
+}
+
+pub enum Fetched {
+    Body(String),
+    /// The server's copy hasn't changed since the time we asked about
+    NotModified,
+}
+
+static SHARED: OnceLock<Client> = OnceLock::new();
+
+/// The client for the whole process, so the spacing between requests holds across commands
+/// that download several things.
+pub fn shared(config: &Config) -> &'static Client {
+    SHARED.get_or_init(|| Client::new(config))
+}
+
+fn user_agent(contact: &Option<String>) -> String {
+    match contact {
+        Some(contact) => format!("{} (+{})", USER_AGENT, contact),
+        None => USER_AGENT.to_string(),
+    }
+}
+
+/// Worth trying again: the server is having a moment, or asked us to slow down.
+fn is_transient(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Client {
+        Client::with_timing(
+            &user_agent(&config.contact),
+            Duration::from_secs(1),
+            3,
+            Duration::from_secs(2),
+        )
+    }
+
+    fn with_timing(
+        user_agent: &str,
+        min_interval: Duration,
+        retries: u32,
+        backoff: Duration,
+    ) -> Client {
+        Client {
+            inner: reqwest::blocking::Client::builder()
+                .user_agent(user_agent)
+                .timeout(Duration::from_secs(30))
+                .build()
+                .unwrap(),
+            min_interval,
+            retries,
+            backoff,
+            last_request: Mutex::new(None),
+        }
+    }
+
+    /// Wait until it's been long enough since the last request.
+    fn throttle(&self) {
+        let mut last_request = self.last_request.lock().unwrap();
+        if let Some(last) = *last_request {
+            let wait = self.min_interval.saturating_sub(last.elapsed());
+            if !wait.is_zero() {
+                log::debug!("Waiting {:.1?} before the next request", wait);
+                thread::sleep(wait);
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+
+    /// Send a request, sending it again if it fails in a way that might go away by itself.
+    /// Each retry waits twice as long as the one before.
+    fn send_with_retries(&self, request: impl Fn() -> RequestBuilder) -> Result<Response, String> {
+        let mut attempt = 0;
+        loop {
+            self.throttle();
+            let error = match request().send() {
+                Ok(response) if !is_transient(response.status()) => return Ok(response),
+                Ok(response) => response.status().to_string(),
+                Err(e) if e.is_connect() || e.is_timeout() => e.to_string(),
+                Err(e) => return Err(e.to_string()),
+            };
+            if attempt == self.retries {
+                return Err(format!(
+                    "Giving up after {} attempts: {}",
+                    attempt + 1,
+                    error
+                ));
+            }
+            let wait = self.backoff * 2u32.pow(attempt);
+            log::warn!("Request failed ({}), trying again in {:.1?}", error, wait);
+            thread::sleep(wait);
+            attempt += 1;
+        }
+    }
+
+    /// Fetch a page, unless it hasn't changed since `if_modified_since`.
+    pub fn get(
+        &self,
+        url: &str,
+        session: &str,
+        if_modified_since: Option<SystemTime>,
+    ) -> Result<Fetched, String> {
+        log::debug!("GET {}", url);
+        let response = self.send_with_retries(|| {
+            let request = self
+                .inner
+                .get(url)
+                .header(header::COOKIE, format!("session={}", session));
+            match if_modified_since {
+                Some(time) => {
+                    request.header(header::IF_MODIFIED_SINCE, httpdate::fmt_http_date(time))
+                }
+                None => request,
+            }
+        })?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified);
+        }
+        if !response.status().is_success() {
+            return Err(format!("{} responded with {}", url, response.status()));
+        }
+        response
+            .text()
+            .map(Fetched::Body)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Post a form. These aren't retried since the server may have acted on the first one
+    /// (submitting the same answer twice counts against you).
+    pub fn post_form(
+        &self,
+        url: &str,
+        session: &str,
+        form: &[(&str, String)],
+    ) -> Result<String, String> {
+        log::debug!("POST {}", url);
+        self.throttle();
+        let response = self
+            .inner
+            .post(url)
+            .header(header::COOKIE, format!("session={}", session))
+            .form(form)
+            .send()
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("{} responded with {}", url, response.status()));
+        }
+        response.text().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::{Client, Fetched};
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+        time::{Duration, Instant, SystemTime},
+    };
+
+    /// A stand-in for adventofcode.com that answers each request with the next canned
+    /// (status, body) pair and remembers the request lines and headers it was sent.
+    pub struct StandIn {
+        pub url: String,
+        pub requests: Arc<Mutex<Vec<Vec<String>>>>,
+    }
+
+    pub fn stand_in(responses: Vec<(u16, &str)>) -> StandIn {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let seen = requests.clone();
+        let responses: Vec<(u16, String)> = responses
+            .into_iter()
+            .map(|(status, body)| (status, body.to_string()))
+            .collect();
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = vec![];
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end().to_string();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length: ") {
+                        content_length = length.parse().unwrap();
+                    }
+                    request.push(line);
+                }
+                let mut form = vec![0; content_length];
+                std::io::Read::read_exact(&mut reader, &mut form).unwrap();
+                request.push(String::from_utf8(form).unwrap());
+                seen.lock().unwrap().push(request);
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Whatever\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        StandIn { url, requests }
+    }
+
+    pub fn quick_client() -> Client {
+        Client::with_timing("aoc-test", Duration::ZERO, 2, Duration::from_millis(1))
+    }
+
+    fn header<'a>(request: &'a [String], name: &str) -> Option<&'a str> {
+        let prefix = format!("{}: ", name);
+        request.iter().find_map(|line| line.strip_prefix(&prefix))
+    }
+
+    #[test]
+    fn test_get_sends_user_agent_and_cookie() {
+        let server = stand_in(vec![(200, "1000\n2000\n")]);
+        let client = Client::with_timing(
+            "aoc-test (+elf@example.com)",
+            Duration::ZERO,
+            0,
+            Duration::ZERO,
+        );
+        match client.get(&format!("{}/2022/day/1/input", server.url), "abc", None) {
+            Ok(Fetched::Body(body)) => assert_eq!(body, "1000\n2000\n"),
+            _ => panic!("expected the input back"),
+        }
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests[0][0], "GET /2022/day/1/input HTTP/1.1");
+        assert_eq!(
+            header(&requests[0], "user-agent"),
+            Some("aoc-test (+elf@example.com)")
+        );
+        assert_eq!(header(&requests[0], "cookie"), Some("session=abc"));
+        assert_eq!(header(&requests[0], "if-modified-since"), None);
+    }
+
+    #[test]
+    fn test_conditional_get() {
+        let server = stand_in(vec![(304, "")]);
+        let since = SystemTime::UNIX_EPOCH + Duration::from_secs(1_670_000_000);
+        let fetched = quick_client().get(&server.url, "abc", Some(since)).unwrap();
+        assert!(matches!(fetched, Fetched::NotModified));
+        assert_eq!(
+            header(&server.requests.lock().unwrap()[0], "if-modified-since"),
+            Some("Fri, 02 Dec 2022 16:53:20 GMT")
+        );
+    }
+
+    #[test]
+    fn test_retries_transient_failures() {
+        let server = stand_in(vec![(503, ""), (429, ""), (200, "ok")]);
+        match quick_client().get(&server.url, "abc", None) {
+            Ok(Fetched::Body(body)) => assert_eq!(body, "ok"),
+            _ => panic!("expected the third attempt to work"),
+        }
+        assert_eq!(server.requests.lock().unwrap().len(), 3);
+
+        let server = stand_in(vec![(500, ""), (500, ""), (500, "")]);
+        assert!(quick_client().get(&server.url, "abc", None).is_err());
+        assert_eq!(server.requests.lock().unwrap().len(), 3);
+
+        // client errors aren't going to fix themselves
+        let server = stand_in(vec![(404, "")]);
+        assert!(quick_client().get(&server.url, "abc", None).is_err());
+        assert_eq!(server.requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_post_is_not_retried() {
+        let server = stand_in(vec![(500, "")]);
+        let form = [("level", "1".to_string()), ("answer", "42".to_string())];
+        assert!(quick_client().post_form(&server.url, "abc", &form).is_err());
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].last().unwrap(), "level=1&answer=42");
+    }
+
+    #[test]
+    fn test_min_interval() {
+        let server = stand_in(vec![(200, "a"), (200, "b")]);
+        let client = Client::with_timing("aoc-test", Duration::from_millis(200), 0, Duration::ZERO);
+        let start = Instant::now();
+        client.get(&server.url, "abc", None).unwrap();
+        client.get(&server.url, "abc", None).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+use crate::site;
+
+/// Checksums of the downloaded inputs, in the format `sha256sum --check` understands.
+pub fn manifest_path(config: &Config) -> PathBuf {
+    config.inputs_dir.join("SHA256SUMS")
+}
+
+pub fn checksum(contents: &str) -> String {
+    Sha256::digest(contents.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn file_name(day: i32) -> String {
+    format!("{:0>2}.txt", day)
+}
+
+/// File name to checksum.
+fn read_manifest(config: &Config) -> BTreeMap<String, String> {
+    let text = std::fs::read_to_string(manifest_path(config)).unwrap_or_default();
+    text.lines()
+        .filter_map(|line| line.split_once("  "))
+        .map(|(sum, name)| (name.to_string(), sum.to_string()))
+        .collect()
+}
+
+/// Remember what a day's input looked like when it was downloaded.
+pub fn record_checksum(config: &Config, day: i32, contents: &str) -> Result<(), String> {
+    let mut manifest = read_manifest(config);
+    manifest.insert(file_name(day), checksum(contents));
+    let text: String = manifest
+        .iter()
+        .map(|(name, sum)| format!("{}  {}\n", sum, name))
+        .collect();
+    let path = manifest_path(config);
+    std::fs::write(&path, text).map_err(|e| format!("Can't write {}: {}", path.display(), e))
+}
+
+/// Whatever's wrong with an input at a glance. Real inputs are never empty, aren't HTML
+/// and always end with a newline, so anything else is an error page or a cut-off download.
+pub fn problems(contents: &str) -> Vec<String> {
+    let mut problems = vec![];
+    let start = contents.trim_start().to_lowercase();
+    if contents.trim().is_empty() {
+        problems.push("it's empty".to_string());
+    } else if start.starts_with("<!doctype") || start.starts_with("<html") {
+        problems.push("it's an HTML page, not a puzzle input".to_string());
+    } else if contents.starts_with("Puzzle inputs differ by user")
+        || contents.starts_with("Please don't repeatedly request")
+    {
+        problems.push(format!(
+            "it's an error message: {}",
+            contents.lines().next().unwrap()
+        ));
+    } else if !contents.ends_with('\n') {
+        problems.push("it doesn't end with a newline, so it's probably truncated".to_string());
+    }
+    problems
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The (year, month, day) that's this many days since 1970-01-01.
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// When a day's puzzle unlocks, in seconds since the epoch: midnight US Eastern time (05:00 UTC).
+pub fn unlock_time(year: i32, day: i32) -> i64 {
+    days_from_civil(year as i64, 12, day as i64) * 86400 + 5 * 3600
+}
+
+/// The days whose puzzles are out at `now`. From 2025 on there are only 12 of them.
+pub fn released_days(year: i32, now: SystemTime) -> Vec<i32> {
+    let last_day = if year >= 2025 { 12 } else { 25 };
+    let now = now.duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+    (1..=last_day)
+        .filter(|&day| unlock_time(year, day) <= now)
+        .collect()
+}
+
+/// Download every input that's out for the configured year. Inputs that are already cached
+/// are only fetched again if they changed.
+pub fn download_all(config: &Config) -> Result<(), String> {
+    let days = released_days(config.year, SystemTime::now());
+    if days.is_empty() {
+        return Err(format!("No puzzles are out for {} yet", config.year));
+    }
+    for day in days {
+        site::download_input(config, day)?;
+    }
+    Ok(())
+}
+
+/// Look over the cached inputs, comparing them against the checksums recorded when they
+/// were downloaded.
+pub fn check(config: &Config) -> Result<(), String> {
+    let manifest = read_manifest(config);
+    let mut checked = 0;
+    let mut broken = 0;
+    for day in 1..=25 {
+        let path = config.input_file_path(day);
+        let expected = manifest.get(&file_name(day));
+        let mut problems = match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let mut problems = problems(&contents);
+                match expected {
+                    Some(sum) if *sum != checksum(&contents) => problems
+                        .push("it changed since it was downloaded (checksum mismatch)".to_string()),
+                    Some(_) => {}
+                    None => log::warn!("No checksum recorded for {}", path.display()),
+                }
+                problems
+            }
+            Err(_) if expected.is_some() => vec!["it's missing".to_string()],
+            Err(_) => continue,
+        };
+        checked += 1;
+        if problems.is_empty() {
+            println!("day {}: ok", day);
+        } else {
+            broken += 1;
+            problems.insert(0, format!("day {}: {}", day, path.display()));
+            println!("{}", problems.join("\n  "));
+        }
+    }
+    if checked == 0 {
+        Err(format!("No inputs in {}", config.inputs_dir.display()))
+    } else if broken > 0 {
+        Err(format!(
+            "{} input(s) need downloading again, e.g. with `download <day>`",
+            broken
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{checksum, civil_from_days, days_from_civil, problems, released_days};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(
+            checksum("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_problems() {
+        assert!(problems("1000\n2000\n\n3000\n").is_empty());
+        assert_eq!(problems("").len(), 1);
+        assert_eq!(problems("\n\n").len(), 1);
+        assert_eq!(problems("1000\n20").len(), 1);
+        assert_eq!(
+            problems("<!DOCTYPE html>\n<html lang=\"en-us\">\n").len(),
+            1
+        );
+        assert_eq!(
+            problems("Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"),
+            vec![
+                "it's an error message: Puzzle inputs differ by user.  Please log in to get your puzzle input."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_released_days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2022, 12, 1), 19327);
+        assert_eq!(civil_from_days(19327), (2022, 12, 1));
+        assert_eq!(civil_from_days(19417), (2023, 3, 1));
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        // 2022-12-01 05:00:00 UTC
+        let day_1 = 19327 * 86400 + 5 * 3600;
+        assert!(released_days(2022, at(day_1 - 1)).is_empty());
+        assert_eq!(released_days(2022, at(day_1)), vec![1]);
+        assert_eq!(released_days(2022, at(day_1 + 3 * 86400)), vec![1, 2, 3, 4]);
+        assert_eq!(released_days(2022, at(day_1 + 365 * 86400)).len(), 25);
+        assert_eq!(released_days(2025, at(day_1 + 4 * 365 * 86400)).len(), 12);
+    }
+}
@@ -0,0 +1,223 @@
+use std::ops::RangeInclusive;
+
+use num::PrimInt;
+
+/// A set of integers kept as sorted, non-overlapping inclusive ranges.
+/// Ranges that touch are merged, so `1..=3` and `4..=6` are stored as `1..=6`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(ranges: I) -> Self {
+        let mut set = IntervalSet::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The smallest number in the set.
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|&(a, _)| a)
+    }
+
+    /// The largest number in the set.
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|&(_, b)| b)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(a, b)| a..=b)
+    }
+
+    /// Add a range, merging it with any it overlaps or touches. Empty ranges are ignored.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut a, mut b) = (*range.start(), *range.end());
+        if a > b {
+            return;
+        }
+        // everything ending before a - 1 is untouched, and so is everything starting after b + 1
+        let first = self
+            .ranges
+            .partition_point(|&(_, end)| end.checked_add(&T::one()).is_some_and(|e| e < a));
+        let last = self
+            .ranges
+            .partition_point(|&(start, _)| b.checked_add(&T::one()).is_none_or(|e| start <= e));
+        if first < last {
+            a = a.min(self.ranges[first].0);
+            b = b.max(self.ranges[last - 1].1);
+        }
+        self.ranges.splice(first..last, [(a, b)]);
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, end)| end < x);
+        i < self.ranges.len() && self.ranges[i].0 <= x
+    }
+
+    /// Whether every number in `range` is in the set.
+    pub fn contains_range(&self, range: RangeInclusive<T>) -> bool {
+        let (a, b) = (*range.start(), *range.end());
+        let i = self.ranges.partition_point(|&(_, end)| end < a);
+        a > b || (i < self.ranges.len() && self.ranges[i].0 <= a && b <= self.ranges[i].1)
+    }
+
+    /// How many numbers are in the set.
+    pub fn covered(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::zero(), |acc, &(a, b)| acc + (b - a) + T::one())
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        for range in other.iter() {
+            set.insert(range);
+        }
+        set
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = self.ranges[i];
+            let (c, d) = other.ranges[j];
+            if a.max(c) <= b.min(d) {
+                ranges.push((a.max(c), b.min(d)));
+            }
+            // whichever ends first can't overlap anything else
+            if b < d {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let ranges = self
+            .ranges
+            .iter()
+            .flat_map(|&(a, b)| other.gaps(a..=b))
+            .map(|range| (*range.start(), *range.end()))
+            .collect();
+        IntervalSet { ranges }
+    }
+
+    /// The ranges within `bounds` that aren't in the set, in order.
+    pub fn gaps(&self, bounds: RangeInclusive<T>) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        let (low, high) = (*bounds.start(), *bounds.end());
+        let mut next = Some(low);
+        let mut ranges = self.ranges.iter();
+        std::iter::from_fn(move || loop {
+            let from = next.filter(|&from| from <= high)?;
+            match ranges.next() {
+                Some(&(_, b)) if b < from => continue,
+                Some(&(a, b)) => {
+                    next = b.checked_add(&T::one());
+                    if a > from {
+                        return Some(from..=(a - T::one()).min(high));
+                    }
+                }
+                None => {
+                    next = None;
+                    return Some(from..=high);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IntervalSet;
+
+    fn ranges(set: &IntervalSet<i32>) -> Vec<(i32, i32)> {
+        set.iter().map(|r| (*r.start(), *r.end())).collect()
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut set = IntervalSet::new();
+        set.insert(10..=12);
+        set.insert(1..=3);
+        set.insert(5..=5);
+        assert_eq!(ranges(&set), vec![(1, 3), (5, 5), (10, 12)]);
+        // touching ranges merge too
+        set.insert(4..=4);
+        assert_eq!(ranges(&set), vec![(1, 5), (10, 12)]);
+        set.insert(7..=20);
+        assert_eq!(ranges(&set), vec![(1, 5), (7, 20)]);
+        set.insert(-3..=30);
+        assert_eq!(ranges(&set), vec![(-3, 30)]);
+        set.insert(5..=4);
+        assert_eq!(ranges(&set), vec![(-3, 30)]);
+        assert_eq!(set.covered(), 34);
+
+        let extremes: IntervalSet<u8> = [250..=255, 0..=3, 4..=4].into_iter().collect();
+        assert_eq!(extremes.covered(), 11);
+        assert_eq!(extremes.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_contains() {
+        let set: IntervalSet<i32> = [1..=3, 6..=9].into_iter().collect();
+        assert!(set.contains(1) && set.contains(3) && set.contains(7));
+        assert!(!set.contains(0) && !set.contains(4) && !set.contains(10));
+        assert!(set.contains_range(6..=9));
+        assert!(set.contains_range(2..=2));
+        assert!(!set.contains_range(2..=6));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet<i32> = [1..=5, 10..=15].into_iter().collect();
+        let b: IntervalSet<i32> = [4..=11, 14..=20].into_iter().collect();
+        assert_eq!(ranges(&a.union(&b)), vec![(1, 20)]);
+        assert_eq!(
+            ranges(&a.intersection(&b)),
+            vec![(4, 5), (10, 11), (14, 15)]
+        );
+        assert_eq!(ranges(&a.difference(&b)), vec![(1, 3), (12, 13)]);
+        assert_eq!(ranges(&b.difference(&a)), vec![(6, 9), (16, 20)]);
+        assert!(a.intersection(&IntervalSet::from(6..=9)).is_empty());
+    }
+
+    #[test]
+    fn test_gaps() {
+        let set: IntervalSet<i32> = [1..=3, 6..=9, 12..=12].into_iter().collect();
+        let gaps: Vec<_> = set.gaps(0..=20).collect();
+        assert_eq!(gaps, vec![0..=0, 4..=5, 10..=11, 13..=20]);
+        let gaps: Vec<_> = set.gaps(2..=7).collect();
+        assert_eq!(gaps, vec![4..=5]);
+        assert_eq!(set.gaps(6..=9).count(), 0);
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::http::{self, Fetched};
+use crate::inputs;
+use crate::site;
+
+/// A private leaderboard, as served by `/<year>/leaderboard/private/view/<id>.json`.
+#[derive(Deserialize, Debug)]
+pub struct Leaderboard {
+    pub event: String,
+    pub members: HashMap<String, Member>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Member {
+    pub id: u64,
+    /// Members who haven't set a name show up anonymously
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32,
+    pub last_star_ts: i64,
+    /// Day to part to when that star was earned
+    pub completion_day_level: HashMap<String, HashMap<String, Star>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    /// When the member got a day's star for a part, in seconds since the epoch.
+    pub fn star_time(&self, day: i32, part: u8) -> Option<i64> {
+        self.completion_day_level
+            .get(&day.to_string())?
+            .get(&part.to_string())
+            .map(|star| star.get_star_ts)
+    }
+}
+
+pub fn parse(json: &str) -> Result<Leaderboard, String> {
+    serde_json::from_str(json).map_err(|e| format!("Can't make sense of the leaderboard: {}", e))
+}
+
+fn fetch(config: &Config, id: u64) -> Result<String, String> {
+    let secret = site::get_secret(config)?;
+    let url = format!(
+        "{}/{}/leaderboard/private/view/{}.json",
+        config.base_url, config.year, id
+    );
+    match http::shared(config).get(&url, &secret, None)? {
+        Fetched::Body(json) => Ok(json),
+        Fetched::NotModified => unreachable!("didn't ask for a conditional request"),
+    }
+}
+
+/// How long something took, AoC style: hours keep counting past a day.
+fn format_duration(seconds: i64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Ranked by score, with ties going to whoever got their last star first.
+fn ranked(leaderboard: &Leaderboard) -> Vec<&Member> {
+    let mut members: Vec<&Member> = leaderboard.members.values().collect();
+    members.sort_by_key(|m| (std::cmp::Reverse(m.local_score), m.last_star_ts, m.id));
+    members
+}
+
+/// The last day anyone has a star for.
+fn last_day(leaderboard: &Leaderboard) -> i32 {
+    (1..=25)
+        .filter(|&day| {
+            leaderboard
+                .members
+                .values()
+                .any(|m| m.star_time(day, 1).is_some())
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn render_standings(leaderboard: &Leaderboard) -> String {
+    let days = last_day(leaderboard);
+    let width = (days as usize).max("days".len());
+    let mut lines = vec![format!(
+        "{:>4}  {:>5}  {:>5}  {:<width$}  name",
+        "",
+        "score",
+        "stars",
+        "days",
+        width = width
+    )];
+    for (rank, member) in ranked(leaderboard).iter().enumerate() {
+        // one column per day: * for both parts, + for just the first
+        let grid: String = (1..=days)
+            .map(
+                |day| match (member.star_time(day, 1), member.star_time(day, 2)) {
+                    (Some(_), Some(_)) => '*',
+                    (Some(_), None) => '+',
+                    _ => '.',
+                },
+            )
+            .collect();
+        lines.push(format!(
+            "{:>3})  {:>5}  {:>5}  {:<width$}  {}",
+            rank + 1,
+            member.local_score,
+            member.stars,
+            grid,
+            member.display_name(),
+            width = width
+        ));
+    }
+    lines.join("\n")
+}
+
+/// When everyone got their stars for a day, counted from when the puzzle unlocked,
+/// and how long part 2 took on top of part 1.
+pub fn render_day(leaderboard: &Leaderboard, day: i32) -> Result<String, String> {
+    let year: i32 = leaderboard.event.parse().map_err(|_| {
+        format!(
+            "The leaderboard is for a strange year: {}",
+            leaderboard.event
+        )
+    })?;
+    let unlocked = inputs::unlock_time(year, day);
+    let mut finishers: Vec<(&Member, i64, Option<i64>)> = leaderboard
+        .members
+        .values()
+        .filter_map(|m| Some((m, m.star_time(day, 1)?, m.star_time(day, 2))))
+        .collect();
+    // whoever finished first goes first, then whoever's closest to finishing
+    finishers.sort_by_key(|&(m, part_1, part_2)| (part_2.unwrap_or(i64::MAX), part_1, m.id));
+    let width = finishers
+        .iter()
+        .map(|(m, _, _)| m.display_name().len())
+        .max()
+        .unwrap_or(0);
+    let mut lines = vec![format!("day {}", day)];
+    if finishers.is_empty() {
+        lines.push("  no stars yet".to_string());
+    }
+    for (member, part_1, part_2) in finishers {
+        let mut line = format!(
+            "  {:<width$}  part 1 {:>8}",
+            member.display_name(),
+            format_duration(part_1 - unlocked),
+            width = width
+        );
+        if let Some(part_2) = part_2 {
+            line += &format!(
+                "  part 2 {:>8}  delta {:>8}",
+                format_duration(part_2 - unlocked),
+                format_duration(part_2 - part_1)
+            );
+        }
+        lines.push(line);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Show a private leaderboard, read from a file or fetched from the site.
+pub fn show(
+    config: &Config,
+    id: Option<u64>,
+    file: &Option<String>,
+    day: Option<i32>,
+) -> Result<(), String> {
+    let json = match (file, id.or(config.leaderboard)) {
+        (Some(path), _) => {
+            std::fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path, e))?
+        }
+        (None, Some(id)) => fetch(config, id)?,
+        (None, None) => {
+            return Err(
+                "Which leaderboard? Give its id, set leaderboard in aoc.toml or use --file"
+                    .to_string(),
+            )
+        }
+    };
+    let leaderboard = parse(&json)?;
+    println!("{}", render_standings(&leaderboard));
+    let days = match day {
+        Some(day) => vec![day],
+        None => (1..=last_day(&leaderboard)).collect(),
+    };
+    for day in days {
+        println!("\n{}", render_day(&leaderboard, day)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fetch, format_duration, parse, render_day, render_standings};
+    use crate::config::Config;
+    use crate::http::test::stand_in;
+
+    fn fixture() -> String {
+        let path = format!("{}/fixtures/leaderboard.json", env!("CARGO_MANIFEST_DIR"));
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "00:00:00");
+        assert_eq!(format_duration(3 * 3600 + 25 * 60 + 7), "03:25:07");
+        assert_eq!(format_duration(30 * 3600), "30:00:00");
+    }
+
+    #[test]
+    fn test_render() {
+        let leaderboard = parse(&fixture()).unwrap();
+        assert_eq!(
+            render_standings(&leaderboard),
+            [
+                "      score  stars  days  name",
+                "  1)     15      4  **    Alice",
+                "  2)     10      3  *+    Bob",
+                "  3)      2      1  +.    (anonymous user #103)",
+                "  4)      0      0  ..    Carol",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            render_day(&leaderboard, 1).unwrap(),
+            [
+                "day 1",
+                "  Alice                  part 1 00:05:00  part 2 00:10:00  delta 00:05:00",
+                "  Bob                    part 1 00:03:00  part 2 00:20:00  delta 00:17:00",
+                "  (anonymous user #103)  part 1 25:00:00",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            render_day(&leaderboard, 3).unwrap(),
+            "day 3\n  no stars yet"
+        );
+    }
+
+    #[test]
+    fn test_fetch() {
+        let cookie = std::env::temp_dir().join(format!("aoc-cookie-test-{}", std::process::id()));
+        std::fs::write(&cookie, "abc\n").unwrap();
+        let server = stand_in(vec![(200, "{}")]);
+        let config = Config {
+            base_url: server.url.clone(),
+            cookie_file: cookie.clone(),
+            ..Config::default()
+        };
+        assert_eq!(fetch(&config, 101).unwrap(), "{}");
+        std::fs::remove_file(cookie).unwrap();
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(
+            requests[0][0],
+            "GET /2022/leaderboard/private/view/101.json HTTP/1.1"
+        );
+        assert!(requests[0].contains(&"cookie: session=abc".to_string()));
+        assert!(parse("{\"event\": \"2022\"}").is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Writes log records to stderr so they never get mixed up with the answers on stdout.
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "[{:<5} {}] {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+/// Map the number of `-v` flags (or `-q`, which is negative) to a level.
+/// Warnings and errors are shown by default.
+pub fn level_filter(verbosity: i32) -> LevelFilter {
+    match verbosity {
+        i32::MIN..=-2 => LevelFilter::Off,
+        -1 => LevelFilter::Error,
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+pub fn init(verbosity: i32) {
+    // set_logger only fails if a logger was already installed, in which case just update the level
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level_filter(verbosity));
+}
+
+#[cfg(test)]
+mod test {
+    use super::level_filter;
+    use log::LevelFilter;
+
+    #[test]
+    fn test_level_filter() {
+        assert_eq!(level_filter(-5), LevelFilter::Off);
+        assert_eq!(level_filter(-1), LevelFilter::Error);
+        assert_eq!(level_filter(0), LevelFilter::Warn);
+        assert_eq!(level_filter(1), LevelFilter::Info);
+        assert_eq!(level_filter(2), LevelFilter::Debug);
+        assert_eq!(level_filter(3), LevelFilter::Trace);
+        assert_eq!(level_filter(10), LevelFilter::Trace);
+    }
+}
@@ -1,64 +1,163 @@
-mod advent;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
+use advent_of_code_2022::{advent, day01, day06, days, repl};
+use clap::Parser;
+
+use crate::cli::{Cli, Command};
+use crate::config::Config;
+use crate::site::Verdict;
+
+mod alloc;
+mod cli;
+mod config;
+mod history;
+mod http;
+mod inputs;
+mod leaderboard;
+mod logger;
+mod runner;
+mod scaffold;
+mod site;
+mod watch;
+
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
+fn submit(
+    config: &Config,
+    day: i32,
+    part: u8,
+    answer: Option<String>,
+    input: &Option<String>,
+) -> Result<(), String> {
+    let answer = match answer {
+        Some(answer) => advent::Answer::Text(answer),
+        None => {
+            let day = days::get(day).ok_or(format!("Day {} isn't solved yet", day))?;
+            if part as usize > day.parts.len() {
+                return Err(format!("Day {} doesn't have a part {}", day.day, part));
+            }
+            let lines = runner::load_input(config, day.day, input)?;
+            runner::solve_part(&day, part as usize, &lines).answer
+        }
+    };
+    match site::submit_answer(config, day, part as usize, &answer)? {
+        Verdict::Correct => println!("{} is the right answer!", answer),
+        Verdict::AlreadySolved => println!("Day {} part {} is already solved", day, part),
+        Verdict::Incorrect(message) | Verdict::TooSoon(message) | Verdict::Unknown(message) => {
+            return Err(message)
+        }
+    }
+    Ok(())
+}
+
+/// Stream a day 1 inventory from a file or stdin, keeping only the elves carrying the most.
+fn top_elves(file: &Option<String>, k: usize) -> Result<(), String> {
+    let top = match file {
+        Some(path) => {
+            let f = std::fs::File::open(path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+            day01::top_from_reader(std::io::BufReader::new(f), k)?
+        }
+        None => day01::top_from_reader(std::io::stdin().lock(), k)?,
+    };
+    let ranked = top.ranked();
+    for (i, total) in &ranked {
+        println!("elf {}: {}", i + 1, total);
+    }
+    println!(
+        "total: {} (out of {} elves)",
+        ranked.iter().map(|(_, total)| total).sum::<u64>(),
+        top.elves()
+    );
+    Ok(())
+}
+
+/// Stream a day 6 datastream from a file or stdin, printing where the markers end.
+fn markers(file: &Option<String>, size: usize, all: bool) -> Result<(), String> {
+    let reader: Box<dyn std::io::Read> = match file {
+        Some(path) => {
+            Box::new(std::fs::File::open(path).map_err(|e| format!("Can't read {}: {}", path, e))?)
+        }
+        None => Box::new(std::io::stdin().lock()),
+    };
+    let mut found = false;
+    for position in day06::markers(reader, size)? {
+        println!("{}", position?);
+        found = true;
+        if !all {
+            break;
+        }
+    }
+    if !found {
+        return Err(format!("No {} bytes in a row are all different", size));
+    }
+    Ok(())
+}
+
+/// Run the tests with UPDATE_SNAPSHOTS set, so the snapshots of rendered output get rewritten.
+fn update_snapshots() -> Result<(), String> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let manifest = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let status = std::process::Command::new(cargo)
+        .args(["test", "--lib", "--manifest-path"])
+        .arg(manifest)
+        .env("UPDATE_SNAPSHOTS", "1")
+        .status()
+        .map_err(|e| format!("Couldn't run cargo: {}", e))?;
+    if !status.success() {
+        return Err("The tests failed, so the snapshots may not all be updated".to_string());
+    }
+    println!("Updated the snapshots in snapshots/, check `git diff` before committing them");
+    Ok(())
+}
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    let mut day = 1;
-    if args.len() != 2 {
-        println!("Assuming day {}", day);
-    } else {
-        day = str::parse(args.get(1).unwrap()).unwrap()
+    let cli = Cli::parse();
+    logger::init(cli.verbosity());
+    let global_args = cli.global_args();
+    let result = config::load(&cli).and_then(|config| run(&config, &global_args, cli.command));
+    if let Err(message) = result {
+        log::error!("{}", message);
+        std::process::exit(1);
     }
-    match day {
-        1 => day01::solve(),
-        2 => day02::solve(),
-        3 => day03::solve(),
-        4 => day04::solve(),
-        5 => day05::solve(),
-        6 => day06::solve(),
-        7 => day07::solve(),
-        8 => day08::solve(),
-        9 => day09::solve(),
-        10 => day10::solve(),
-        11 => day11::solve(),
-        12 => day12::solve(),
-        13 => day13::solve(),
-        14 => day14::solve(),
-        15 => day15::solve(),
-        16 => day16::solve(),
-        17 => day17::solve(),
-        18 => day18::solve(),
-        19 => day19::solve(),
-        20 => day20::solve(),
-        21 => day21::solve(),
-        22 => day22::solve(),
-        23 => day23::solve(),
-        24 => day24::solve(),
-        25 => day25::solve(),
-        _ => println!("That day isn't solved yet"),
+}
+
+fn run(config: &Config, global_args: &[String], command: Command) -> Result<(), String> {
+    runner::init_thread_pool(config.jobs);
+    match command {
+        Command::Run {
+            day,
+            no_history,
+            serial,
+            solve,
+        } => runner::run(config, day, !no_history, serial, &solve),
+        Command::Bench {
+            day,
+            iterations,
+            solve,
+        } => runner::bench(config, day, iterations, &solve),
+        Command::Verify {
+            day,
+            example,
+            record,
+            solve,
+        } => runner::verify(config, day, example, record, &solve),
+        Command::Watch { day, input } => watch::watch(config, global_args, day, &input),
+        Command::Download { day: Some(day), .. } => site::download_input(config, day),
+        Command::Download { day: None, .. } => inputs::download_all(config),
+        Command::History { day, part, limit } => history::show(config, day, part, limit),
+        Command::CheckInputs => inputs::check(config),
+        Command::Leaderboard { id, file, day } => leaderboard::show(config, id, &file, day),
+        Command::Submit {
+            day,
+            part,
+            answer,
+            input,
+        } => submit(config, day, part, answer, &input),
+        Command::TopElves { file, k } => top_elves(&file, k),
+        Command::Markers { file, size, all } => markers(&file, size, all),
+        Command::UpdateSnapshots => update_snapshots(),
+        Command::New { day } => scaffold::new_day(day),
+        Command::Repl { day, input } => {
+            runner::load_input(config, day, &input).and_then(|lines| repl::run(day, &lines))
+        }
     }
 }
@@ -0,0 +1,57 @@
+use std::io::{BufRead, Write};
+
+/// A parsed puzzle model that can be poked at interactively.
+pub trait Explorer {
+    /// Usage lines for the commands this day supports.
+    fn help(&self) -> Vec<&'static str>;
+    /// Answer a single query, or explain what went wrong.
+    fn query(&self, command: &str, args: &[&str]) -> Result<String, String>;
+}
+
+fn explorer(day: i32, lines: &[String]) -> Option<Box<dyn Explorer>> {
+    match day {
+        1 => Some(crate::day01::explorer(lines)),
+        2 => Some(crate::day02::explorer(lines)),
+        3 => Some(crate::day03::explorer(lines)),
+        4 => Some(crate::day04::explorer(lines)),
+        5 => Some(crate::day05::explorer(lines)),
+        7 => Some(crate::day07::explorer(lines)),
+        16 => Some(crate::day16::explorer(lines)),
+        21 => Some(crate::day21::explorer(lines)),
+        _ => None,
+    }
+}
+
+pub fn run(day: i32, lines: &[String]) -> Result<(), String> {
+    let explorer = explorer(day, lines).ok_or(format!("Day {} doesn't have a REPL", day))?;
+    println!("Loaded day {}, type `help` for commands", day);
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("day{:0>2}> ", day);
+        std::io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(line) => line.unwrap(),
+            // EOF
+            None => break,
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            ["quit"] | ["exit"] => break,
+            ["help"] => {
+                for usage in explorer.help() {
+                    println!("  {}", usage);
+                }
+                println!("  help");
+                println!("  quit");
+            }
+            [command, args @ ..] => match explorer.query(command, args) {
+                Ok(result) => println!("{}", result),
+                Err(message) => println!("error: {}", message),
+            },
+        }
+    }
+    println!();
+    Ok(())
+}
@@ -0,0 +1,342 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+use serde_json::json;
+
+use crate::advent::{self, Answer};
+use crate::alloc::{self, MemoryUsage};
+use crate::cli::{Format, SolveArgs};
+use crate::config::Config;
+use crate::days::{self, Day};
+use crate::history;
+use crate::site;
+
+pub struct PartResult {
+    pub day: i32,
+    pub part: usize,
+    pub answer: Answer,
+    /// Only comparable between runs if nothing else was being solved at the same time
+    pub duration: Duration,
+    /// Only meaningful if nothing else was being solved at the same time
+    pub memory: MemoryUsage,
+}
+
+/// The days to work on: just the one that was asked for, or all of them.
+fn select_days(day: Option<i32>, args: &SolveArgs) -> Result<Vec<Day>, String> {
+    match day {
+        Some(day) => Ok(vec![
+            days::get(day).ok_or(format!("Day {} isn't solved yet", day))?
+        ]),
+        None if args.input.is_some() => Err("--input needs a day to go with it".to_string()),
+        None => Ok(days::all()),
+    }
+}
+
+/// The parts of a day to solve, numbered from 1.
+fn select_parts(day: &Day, args: &SolveArgs) -> Result<Vec<usize>, String> {
+    match args.part {
+        Some(part) if part as usize <= day.parts.len() => Ok(vec![part as usize]),
+        Some(part) => Err(format!("Day {} doesn't have a part {}", day.day, part)),
+        None => Ok((1..=day.parts.len()).collect()),
+    }
+}
+
+/// Size the thread pool shared by `run` and the solvers that parallelize internally.
+pub fn init_thread_pool(jobs: Option<u16>) {
+    log::debug!("Solving with {:?} threads", jobs);
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0) as usize)
+        // some solvers recurse pretty deep, like day 12's walk and day 18's flood fill
+        .stack_size(64 * 1024 * 1024)
+        .build_global()
+        .unwrap();
+}
+
+pub fn load_input(
+    config: &Config,
+    day: i32,
+    input: &Option<String>,
+) -> Result<Vec<String>, String> {
+    match input {
+        Some(path) => advent::read_input_file(path),
+        None => site::read_input(config, day),
+    }
+}
+
+pub fn solve_part(day: &Day, part: usize, lines: &[String]) -> PartResult {
+    log::debug!("Solving day {} part {}", day.day, part);
+    let start = Instant::now();
+    let (answer, memory) = alloc::measure(|| day.parts[part - 1](lines));
+    let duration = start.elapsed();
+    PartResult {
+        day: day.day,
+        part,
+        answer,
+        duration,
+        memory,
+    }
+}
+
+fn describe_memory(memory: &MemoryUsage) -> String {
+    format!(
+        "peak {} in {} allocations",
+        alloc::format_bytes(memory.peak_bytes),
+        memory.allocations
+    )
+}
+
+fn print_result(result: &PartResult, format: Format, args: &SolveArgs) {
+    match format {
+        Format::Text => {
+            let answer = result.answer.to_string();
+            // multi-line answers (looking at you, day 10) get to start on a line of their own
+            let separator = if answer.contains('\n') { "\n" } else { " " };
+            let mut stats = format!("{:.1?}", result.duration);
+            if args.profile_mem {
+                stats = format!("{}, {}", stats, describe_memory(&result.memory));
+            }
+            println!(
+                "day {} part {} ({}):{}{}",
+                result.day, result.part, stats, separator, answer
+            );
+        }
+        Format::Json => {
+            let mut json = json!({
+                "day": result.day,
+                "part": result.part,
+                "answer": result.answer.to_string(),
+                "seconds": result.duration.as_secs_f64(),
+            });
+            if args.profile_mem {
+                json["peak_bytes"] = json!(result.memory.peak_bytes);
+                json["allocations"] = json!(result.memory.allocations);
+            }
+            println!("{}", json);
+        }
+    }
+}
+
+pub fn run(
+    config: &Config,
+    day: Option<i32>,
+    record_history: bool,
+    serial: bool,
+    args: &SolveArgs,
+) -> Result<(), String> {
+    if args.profile_mem {
+        alloc::enable();
+    }
+    let days = select_days(day, args)?;
+    // Every day is independent, so solve them all at once and print them in order afterwards.
+    // Parts solved side by side get in each other's way though, so clean timings need
+    // --serial, and counting memory always solves one part at a time, as it's counted across
+    // all threads. The solvers that parallelize internally still do.
+    let serial = serial || args.profile_mem;
+    // Fetch the inputs one at a time so we don't hammer adventofcode.com with downloads
+    let loading = Mutex::new(());
+    let solve_day = |day: &Day| -> Result<Vec<PartResult>, String> {
+        let parts = select_parts(day, args)?;
+        let lines = {
+            let _loading = loading.lock().unwrap();
+            load_input(config, day.day, &args.input)?
+        };
+        let solve = |&part: &usize| solve_part(day, part, &lines);
+        Ok(if serial {
+            parts.iter().map(solve).collect()
+        } else {
+            parts.par_iter().map(solve).collect()
+        })
+    };
+    let solved: Vec<Result<Vec<PartResult>, String>> = if serial {
+        days.iter().map(solve_day).collect()
+    } else {
+        days.par_iter().map(solve_day).collect()
+    };
+    // one day that can't be solved doesn't stop the others being reported
+    let mut results = vec![];
+    let mut errors = vec![];
+    for (day, solved) in days.iter().zip(solved) {
+        match solved {
+            Ok(day_results) => {
+                for result in day_results.iter() {
+                    print_result(result, args.format.unwrap_or(config.format), args);
+                }
+                results.extend(day_results);
+            }
+            Err(message) => errors.push(format!("Day {}: {}", day.day, message)),
+        }
+    }
+    if record_history {
+        // the answers are out already, so a history that can't be written isn't worth failing over
+        if let Err(message) = history::record(config, &results, &args.input) {
+            log::warn!("{}", message);
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+pub fn bench(
+    config: &Config,
+    day: Option<i32>,
+    iterations: u32,
+    args: &SolveArgs,
+) -> Result<(), String> {
+    if iterations == 0 {
+        return Err("Need at least one iteration to benchmark".to_string());
+    }
+    if args.profile_mem {
+        alloc::enable();
+    }
+    for day in select_days(day, args)? {
+        let parts = select_parts(&day, args)?;
+        let lines = load_input(config, day.day, &args.input)?;
+        for part in parts {
+            let results: Vec<PartResult> = (0..iterations)
+                .map(|_| solve_part(&day, part, &lines))
+                .collect();
+            let durations: Vec<Duration> = results.iter().map(|r| r.duration).collect();
+            let min = *durations.iter().min().unwrap();
+            let max = *durations.iter().max().unwrap();
+            let mean = durations.iter().sum::<Duration>() / iterations;
+            // every iteration does the same work, so the worst one is representative
+            let memory = MemoryUsage {
+                peak_bytes: results.iter().map(|r| r.memory.peak_bytes).max().unwrap(),
+                allocations: results.iter().map(|r| r.memory.allocations).max().unwrap(),
+            };
+            match args.format.unwrap_or(config.format) {
+                Format::Text => {
+                    let mut summary = format!(
+                        "day {} part {}: mean {:.1?}, min {:.1?}, max {:.1?} over {} iterations",
+                        day.day, part, mean, min, max, iterations
+                    );
+                    if args.profile_mem {
+                        summary = format!("{}, {}", summary, describe_memory(&memory));
+                    }
+                    println!("{}", summary);
+                }
+                Format::Json => {
+                    let mut json = json!({
+                        "day": day.day,
+                        "part": part,
+                        "iterations": iterations,
+                        "mean_seconds": mean.as_secs_f64(),
+                        "min_seconds": min.as_secs_f64(),
+                        "max_seconds": max.as_secs_f64(),
+                    });
+                    if args.profile_mem {
+                        json["peak_bytes"] = json!(memory.peak_bytes);
+                        json["allocations"] = json!(memory.allocations);
+                    }
+                    println!("{}", json);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Answers are recorded one per line, next to the input they belong to.
+/// Multi-line answers have their newlines escaped.
+pub fn answers_file_path(input_path: &str) -> String {
+    match input_path.strip_suffix(".txt") {
+        Some(stem) => format!("{}.answers", stem),
+        None => format!("{}.answers", input_path),
+    }
+}
+
+fn escape_answer(answer: &Answer) -> String {
+    answer.to_string().replace('\n', "\\n")
+}
+
+pub fn verify(
+    config: &Config,
+    day: Option<i32>,
+    example: bool,
+    record: bool,
+    args: &SolveArgs,
+) -> Result<(), String> {
+    if example && args.input.is_some() {
+        return Err("--example and --input can't be used together".to_string());
+    }
+    let mut failures = 0;
+    for day in select_days(day, args)? {
+        let input_path = match &args.input {
+            Some(path) => path.clone(),
+            None if example => config.example_file_path(day.day).display().to_string(),
+            None => config.input_file_path(day.day).display().to_string(),
+        };
+        let answers_path = answers_file_path(&input_path);
+        if example && !std::path::Path::new(&input_path).exists() {
+            log::warn!("Day {} doesn't have an example, skipping", day.day);
+            continue;
+        }
+        let lines = load_input(config, day.day, &Some(input_path))?;
+        if record {
+            let answers: Vec<String> = (1..=day.parts.len())
+                .map(|part| escape_answer(&solve_part(&day, part, &lines).answer))
+                .collect();
+            std::fs::write(&answers_path, answers.join("\n") + "\n")
+                .map_err(|e| format!("Can't write {}: {}", answers_path, e))?;
+            println!("day {}: recorded answers in {}", day.day, answers_path);
+            continue;
+        }
+        let expected: Vec<String> = match std::fs::read_to_string(&answers_path) {
+            Ok(text) => text.lines().map(str::to_string).collect(),
+            Err(_) => {
+                log::warn!("No answers recorded in {}, skipping", answers_path);
+                continue;
+            }
+        };
+        for part in select_parts(&day, args)? {
+            let result = solve_part(&day, part, &lines);
+            let actual = escape_answer(&result.answer);
+            match expected.get(part - 1) {
+                None => println!("day {} part {}: no answer recorded", day.day, part),
+                Some(expected) if expected == &actual => {
+                    println!(
+                        "day {} part {}: ok ({:.1?})",
+                        day.day, part, result.duration
+                    )
+                }
+                Some(expected) => {
+                    failures += 1;
+                    println!(
+                        "day {} part {}: FAILED, expected {} but got {}",
+                        day.day, part, expected, actual
+                    );
+                }
+            }
+        }
+    }
+    if failures > 0 {
+        Err(format!("{} answer(s) didn't match", failures))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{answers_file_path, escape_answer};
+    use crate::advent::Answer;
+
+    #[test]
+    fn test_answers_file_path() {
+        assert_eq!(answers_file_path("examples/07.txt"), "examples/07.answers");
+        assert_eq!(answers_file_path("big-input"), "big-input.answers");
+    }
+
+    #[test]
+    fn test_escape_answer() {
+        assert_eq!(escape_answer(&Answer::from(12)), "12");
+        assert_eq!(
+            escape_answer(&Answer::from("#..#\n.##.".to_string())),
+            "#..#\\n.##."
+        );
+    }
+}
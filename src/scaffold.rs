@@ -0,0 +1,30 @@
+use std::path::Path;
+
+const TEMPLATE: &str = "pub fn solve_1(lines: &[String]) -> usize {
+    todo!(\"part 1, given {} lines\", lines.len())
+}
+
+pub fn solve_2(lines: &[String]) -> usize {
+    todo!(\"part 2, given {} lines\", lines.len())
+}
+";
+
+/// Write a skeleton source file for a day, and say how to hook it up.
+pub fn new_day(day: i32) -> Result<(), String> {
+    let path = format!("src/day{:0>2}.rs", day);
+    if Path::new(&path).exists() {
+        return Err(format!("{} already exists", path));
+    }
+    std::fs::write(&path, TEMPLATE).map_err(|e| format!("Can't write {}: {}", path, e))?;
+    println!("Created {}. To finish setting it up:", path);
+    println!("  * add `pub mod day{:0>2};` to src/lib.rs", day);
+    println!(
+        "  * add `day!({}, day{:0>2}::solve_1, day{:0>2}::solve_2),` to src/days.rs",
+        day, day, day
+    );
+    println!(
+        "  * paste the example into examples/{:0>2}.txt and its answers into examples/{:0>2}.answers",
+        day, day
+    );
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use std::{
+    fs::File,
+    io::{Read, Write},
+};
+
+use crate::advent::Answer;
+use crate::config::Config;
+use crate::http::{self, Fetched};
+use crate::inputs;
+
+pub fn get_secret(config: &Config) -> Result<String, String> {
+    let path = &config.cookie_file;
+    let mut f = File::open(path).map_err(|e| {
+        format!(
+            "Can't read the session cookie from {}: {}",
+            path.display(),
+            e
+        )
+    })?;
+    let mut buffer = String::new();
+    f.read_to_string(&mut buffer).unwrap();
+    Ok(buffer.trim().to_string())
+}
+
+fn write_input_file(config: &Config, day: i32, buffer: &str) -> Result<(), String> {
+    let path = config.input_file_path(day);
+    let error = |e: std::io::Error| format!("Can't write {}: {}", path.display(), e);
+    std::fs::create_dir_all(&config.inputs_dir).map_err(error)?;
+    let mut f = File::create(&path).map_err(error)?;
+    f.write_all(buffer.as_bytes()).map_err(error)
+}
+
+/// Download the input for a day. If it's already cached, the server is asked to only send it
+/// if it changed since.
+pub fn download_input(config: &Config, day: i32) -> Result<(), String> {
+    log::info!("Downloading input for day {}", day);
+    let secret = get_secret(config)?;
+    let cached = std::fs::metadata(config.input_file_path(day))
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let url = format!("{}/{}/day/{}/input", config.base_url, config.year, day);
+    let text = match http::shared(config)
+        .get(&url, &secret, cached)
+        .map_err(|e| format!("Couldn't download input for day {}: {}", day, e))?
+    {
+        Fetched::Body(text) => text,
+        Fetched::NotModified => {
+            log::info!("Input for day {} hasn't changed", day);
+            let path = config.input_file_path(day);
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+            return inputs::record_checksum(config, day, &text);
+        }
+    };
+    let problems = inputs::problems(&text);
+    if !problems.is_empty() {
+        return Err(format!(
+            "Not saving the input for day {} because {}",
+            day,
+            problems.join(", ")
+        ));
+    }
+    write_input_file(config, day, &text)?;
+    inputs::record_checksum(config, day, &text)?;
+    log::info!(
+        "Saved input for day {} to {}",
+        day,
+        config.input_file_path(day).display()
+    );
+    Ok(())
+}
+
+/// Read the input for a day, downloading it first if it isn't cached yet.
+pub fn read_input(config: &Config, day: i32) -> Result<Vec<String>, String> {
+    let path = config.input_file_path(day);
+    if !path.exists() {
+        download_input(config, day)?;
+    } else {
+        log::debug!("Using cached input {}", path.display());
+    }
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+    let problems = inputs::problems(&text);
+    if !problems.is_empty() {
+        return Err(format!(
+            "{} looks broken because {}, try `download {}`",
+            path.display(),
+            problems.join(", "),
+            day
+        ));
+    }
+    Ok(text.lines().map(str::to_string).collect())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect(String),
+    TooSoon(String),
+    AlreadySolved,
+    Unknown(String),
+}
+
+/// Pick the verdict out of the HTML page AoC responds with after submitting an answer.
+fn parse_verdict(html: &str) -> Verdict {
+    let article = match (html.find("<article>"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start + "<article>".len()..end],
+        _ => html,
+    };
+    // strip the markup so the message can be shown on the terminal
+    let mut message = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => message.push(c),
+            _ => {}
+        }
+    }
+    let message = message.trim().to_string();
+    if message.contains("That's the right answer") {
+        Verdict::Correct
+    } else if message.contains("That's not the right answer") {
+        Verdict::Incorrect(message)
+    } else if message.contains("You gave an answer too recently") {
+        Verdict::TooSoon(message)
+    } else if message.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown(message)
+    }
+}
+
+pub fn submit_answer(
+    config: &Config,
+    day: i32,
+    part: usize,
+    answer: &Answer,
+) -> Result<Verdict, String> {
+    log::info!("Submitting {} for day {} part {}", answer, day, part);
+    let secret = get_secret(config)?;
+    let url = format!("{}/{}/day/{}/answer", config.base_url, config.year, day);
+    let html = http::shared(config).post_form(
+        &url,
+        &secret,
+        &[("level", part.to_string()), ("answer", answer.to_string())],
+    )?;
+    Ok(parse_verdict(&html))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_verdict, Verdict};
+
+    #[test]
+    fn test_parse_verdict() {
+        let page = |message: &str| {
+            format!(
+                "<html><main>\n<article><p>{}</p></article>\n</main></html>",
+                message
+            )
+        };
+        assert_eq!(
+            parse_verdict(&page(
+                "That's the right answer! You are <em>one gold star</em> closer."
+            )),
+            Verdict::Correct
+        );
+        assert_eq!(
+            parse_verdict(&page(
+                "That's not the right answer; your answer is too high."
+            )),
+            Verdict::Incorrect("That's not the right answer; your answer is too high.".into())
+        );
+        assert_eq!(
+            parse_verdict(&page(
+                "You gave an answer too recently; you have 41s left to wait."
+            )),
+            Verdict::TooSoon("You gave an answer too recently; you have 41s left to wait.".into())
+        );
+        assert_eq!(
+            parse_verdict(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Verdict::AlreadySolved
+        );
+    }
+}
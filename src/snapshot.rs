@@ -0,0 +1,84 @@
+// Compares rendered text (CRT screens, stack diagrams, maps) against a copy kept in snapshots/,
+// so changes to how things look show up as test failures. When a change is intended, run
+// the tests with UPDATE_SNAPSHOTS=1 set (or the `update-snapshots` command) to rewrite them.
+
+use std::path::PathBuf;
+
+const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(format!("{}.txt", name))
+}
+
+/// Where `expected` and `actual` first differ, as a message pointing out the line.
+fn first_difference(expected: &str, actual: &str) -> Option<String> {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    for number in 1.. {
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => break,
+            (e, a) if e == a => continue,
+            (e, a) => {
+                return Some(format!(
+                    "line {} differs\n  expected: {}\n    actual: {}",
+                    number,
+                    e.unwrap_or("(nothing)"),
+                    a.unwrap_or("(nothing)")
+                ))
+            }
+        }
+    }
+    (expected != actual).then(|| "trailing newlines differ".to_string())
+}
+
+/// Check `actual` against the snapshot called `name`, or save it as the new snapshot if
+/// UPDATE_SNAPSHOTS is set.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+    let actual = format!("{}\n", actual.trim_end_matches('\n'));
+    if std::env::var_os(UPDATE_VAR).is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "No snapshot in {} yet, run with {}=1 to save this one:\n{}",
+            path.display(),
+            UPDATE_VAR,
+            actual
+        )
+    });
+    if let Some(difference) = first_difference(&expected, &actual) {
+        panic!(
+            "{} doesn't match the snapshot in {}, {}\n\
+             (run with {}=1 if that's intended)\nthe whole thing:\n{}",
+            name,
+            path.display(),
+            difference,
+            UPDATE_VAR,
+            actual
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::first_difference;
+
+    #[test]
+    fn test_first_difference() {
+        assert_eq!(first_difference("#.\n.#\n", "#.\n.#\n"), None);
+        assert_eq!(
+            first_difference("#.\n.#\n", "#.\n##\n").unwrap(),
+            "line 2 differs\n  expected: .#\n    actual: ##"
+        );
+        assert_eq!(
+            first_difference("#.\n", "#.\n.#\n").unwrap(),
+            "line 2 differs\n  expected: (nothing)\n    actual: .#"
+        );
+        assert!(first_difference("#.\n", "#.").is_some());
+    }
+}
@@ -0,0 +1,101 @@
+// A C-style interface to the solvers for the `wasm32-unknown-unknown` build, so a page can
+// load the module with nothing but `WebAssembly.instantiate` and no glue code:
+//
+//   let ptr = aoc_alloc(len);          copy the input's UTF-8 bytes to ptr
+//   let ok = aoc_solve(day, part, ptr, len);
+//   aoc_free(ptr, len);
+//   read aoc_output_len() bytes at aoc_output_ptr(): the answer if ok is 1, else the error
+//
+// Puzzle inputs that make a solver panic trap the module instead of returning an error.
+
+use std::sync::Mutex;
+
+use crate::days;
+
+static OUTPUT: Mutex<String> = Mutex::new(String::new());
+
+/// Reserve `len` bytes for the caller to write an input into.
+#[no_mangle]
+pub extern "C" fn aoc_alloc(len: usize) -> *mut u8 {
+    let mut buffer = Vec::<u8>::with_capacity(len);
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+    ptr
+}
+
+/// Give back memory from `aoc_alloc`.
+///
+/// # Safety
+/// `ptr` and `len` have to be exactly what `aoc_alloc` was called with and returned.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_free(ptr: *mut u8, len: usize) {
+    drop(Vec::from_raw_parts(ptr, 0, len));
+}
+
+/// Solve one part of a day for the input at `ptr`. Returns 1 if it was solved and 0 if not,
+/// with the answer or the reason it wasn't left in the output.
+///
+/// # Safety
+/// `ptr` has to point at `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(day: i32, part: u32, ptr: *const u8, len: usize) -> i32 {
+    let bytes = std::slice::from_raw_parts(ptr, len);
+    let result = std::str::from_utf8(bytes)
+        .map_err(|e| format!("The input isn't UTF-8: {}", e))
+        .and_then(|input| days::solve(day, part as usize, input));
+    let mut output = OUTPUT.lock().unwrap();
+    match result {
+        Ok(answer) => {
+            *output = answer.to_string();
+            1
+        }
+        Err(message) => {
+            *output = message;
+            0
+        }
+    }
+}
+
+/// Where the output of the last `aoc_solve` is. It stays put until the next one.
+#[no_mangle]
+pub extern "C" fn aoc_output_ptr() -> *const u8 {
+    OUTPUT.lock().unwrap().as_ptr()
+}
+
+#[no_mangle]
+pub extern "C" fn aoc_output_len() -> usize {
+    OUTPUT.lock().unwrap().len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{aoc_alloc, aoc_free, aoc_output_len, aoc_output_ptr, aoc_solve};
+
+    /// Go through the same steps a page would, natively.
+    fn solve(day: i32, part: u32, input: &[u8]) -> (i32, String) {
+        let ptr = aoc_alloc(input.len());
+        let (solved, output) = unsafe {
+            std::ptr::copy_nonoverlapping(input.as_ptr(), ptr, input.len());
+            let solved = aoc_solve(day, part, ptr, input.len());
+            aoc_free(ptr, input.len());
+            let output = std::slice::from_raw_parts(aoc_output_ptr(), aoc_output_len());
+            (solved, String::from_utf8(output.to_vec()).unwrap())
+        };
+        (solved, output)
+    }
+
+    #[test]
+    fn test_solve() {
+        let example = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/01.txt"));
+        assert_eq!(solve(1, 1, &example.unwrap()), (1, "24000".to_string()));
+        assert_eq!(
+            solve(1, 3, b"1\n"),
+            (0, "Day 1 doesn't have a part 3".to_string())
+        );
+        assert_eq!(solve(1, 1, &[0xff]).0, 0);
+        assert_eq!(
+            solve(26, 1, b""),
+            (0, "Day 26 isn't solved yet".to_string())
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use regex::Regex;
+
+use crate::config::Config;
+use crate::runner;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A day's source and the source of every module it uses, and the modules they use, apart
+/// from other days (the repl reaches all of them). `advent.rs` is always there, since it
+/// has the answers every day returns.
+fn source_files(day: i32) -> Vec<PathBuf> {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let use_re = Regex::new(r"crate::(?:\{([^}]*)\}|(\w+))").unwrap();
+    let day_re = Regex::new(r"^day[0-9]+$").unwrap();
+    let own = format!("day{:0>2}", day);
+    let mut modules = vec![own.clone(), "advent".to_string()];
+    let mut i = 0;
+    while i < modules.len() {
+        let text = std::fs::read_to_string(src.join(format!("{}.rs", modules[i])));
+        i += 1;
+        let Ok(text) = text else {
+            continue;
+        };
+        for captures in use_re.captures_iter(&text) {
+            let names = match (captures.get(1), captures.get(2)) {
+                (Some(list), _) => list.as_str().split(',').collect(),
+                (_, Some(name)) => vec![name.as_str()],
+                _ => vec![],
+            };
+            for name in names {
+                let name = name.trim().split("::").next().unwrap_or("");
+                let known = modules.iter().any(|module| module == name);
+                if !name.is_empty() && !known && !day_re.is_match(name) {
+                    modules.push(name.to_string());
+                }
+            }
+        }
+    }
+    modules
+        .iter()
+        .map(|module| src.join(format!("{}.rs", module)))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Everything a day's results depend on: its source and the shared modules it uses, its
+/// example and its input, along with the answers recorded for them.
+fn watched_files(config: &Config, day: i32, input: &Option<String>) -> Vec<PathBuf> {
+    let example = config.example_file_path(day).display().to_string();
+    let input = match input {
+        Some(path) => path.clone(),
+        None => config.input_file_path(day).display().to_string(),
+    };
+    let mut paths = source_files(day);
+    paths.extend([
+        PathBuf::from(runner::answers_file_path(&example)),
+        PathBuf::from(example),
+        PathBuf::from(runner::answers_file_path(&input)),
+        PathBuf::from(input),
+    ]);
+    // the input can be the example
+    let mut seen = HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+    paths
+}
+
+/// When each file was last modified, or `None` if it doesn't exist (yet).
+fn modified_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+fn changed<'a>(
+    paths: &'a [PathBuf],
+    before: &[Option<SystemTime>],
+    after: &[Option<SystemTime>],
+) -> Vec<&'a PathBuf> {
+    paths
+        .iter()
+        .zip(before.iter().zip(after))
+        .filter(|(_, (before, after))| before != after)
+        .map(|(path, _)| path)
+        .collect()
+}
+
+/// Run this tool through cargo, so that changes to the source are built first.
+/// Returns whether it succeeded.
+fn cargo_run(global_args: &[String], args: &[String]) -> bool {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let mut command = Command::new(cargo);
+    command
+        .arg("run")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(manifest);
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    command.arg("--").args(global_args).args(args);
+    match command.status() {
+        Ok(status) => status.success(),
+        Err(e) => {
+            log::error!("Couldn't run cargo: {}", e);
+            false
+        }
+    }
+}
+
+/// Check the examples, then solve the real input.
+fn run_day(global_args: &[String], day: i32, input: &Option<String>) {
+    let day = day.to_string();
+    let examples_ok = cargo_run(
+        global_args,
+        &["verify".into(), day.clone(), "--example".into()],
+    );
+    let mut args = vec!["run".to_string(), day, "--no-history".to_string()];
+    if let Some(input) = input {
+        args.extend(["--input".to_string(), input.clone()]);
+    }
+    let run_ok = cargo_run(global_args, &args);
+    if !examples_ok || !run_ok {
+        println!("(something went wrong, see above)");
+    }
+}
+
+/// Re-run a day whenever its source, example or input changes, until interrupted.
+/// `global_args` are the options this was started with, so the runs see the same settings.
+pub fn watch(
+    config: &Config,
+    global_args: &[String],
+    day: i32,
+    input: &Option<String>,
+) -> Result<(), String> {
+    let paths = watched_files(config, day, input);
+    for path in &paths {
+        log::info!("Watching {}", path.display());
+    }
+    let mut times = modified_times(&paths);
+    run_day(global_args, day, input);
+    loop {
+        println!("Waiting for changes to day {}... (Ctrl-C to stop)", day);
+        let changes = loop {
+            thread::sleep(POLL_INTERVAL);
+            let now = modified_times(&paths);
+            if now != times {
+                // editors often save in several steps, so let things settle first
+                thread::sleep(POLL_INTERVAL);
+                let settled = modified_times(&paths);
+                let changes: Vec<String> = changed(&paths, &times, &settled)
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                times = settled;
+                if !changes.is_empty() {
+                    break changes;
+                }
+            }
+        };
+        println!("\n--- {} changed ---", changes.join(", "));
+        run_day(global_args, day, input);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{changed, modified_times, source_files, watched_files};
+    use crate::config::Config;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_watched_files() {
+        let paths = watched_files(&Config::default(), 7, &Some("big.txt".to_string()));
+        assert!(paths[0].ends_with("src/day07.rs"));
+        assert!(paths[1].ends_with("src/advent.rs"));
+        assert!(paths.iter().any(|path| path.ends_with("src/repl.rs")));
+        assert_eq!(
+            paths[paths.len() - 4..],
+            [
+                "examples/07.answers",
+                "examples/07.txt",
+                "big.answers",
+                "big.txt"
+            ]
+            .map(PathBuf::from)
+        );
+        let sources = source_files(7).len();
+        let paths = watched_files(&Config::default(), 7, &Some("examples/07.txt".to_string()));
+        assert_eq!(paths.len(), sources + 2);
+
+        // the shared modules a day uses, but not the other days
+        let paths = source_files(4);
+        assert!(paths.iter().any(|path| path.ends_with("src/intervals.rs")));
+        assert!(!paths.iter().any(|path| path.ends_with("src/day07.rs")));
+    }
+
+    #[test]
+    fn test_changed() {
+        let file = std::env::temp_dir().join(format!("aoc-watch-test-{}", std::process::id()));
+        let paths = vec![PathBuf::from("Cargo.toml"), file.clone()];
+        let before = modified_times(&paths);
+        assert!(before[0].is_some() && before[1].is_none());
+        std::fs::write(&file, "1\n").unwrap();
+        let after = modified_times(&paths);
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(changed(&paths, &before, &after), vec![&file]);
+        assert!(changed(&paths, &after, &after).is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+// Runs the WebAssembly build of the solvers in an interpreter, the way a page would use it.
+// The module has to be built first, which needs the wasm32 target, so these tests only run
+// when asked for:
+//
+//   rustup target add wasm32-unknown-unknown
+//   cargo build --lib --release --target wasm32-unknown-unknown --no-default-features
+//   cargo test --test wasm -- --ignored
+
+use std::path::Path;
+
+use wasmi::{Engine, Instance, Linker, Module, Store};
+
+struct Solver {
+    store: Store<()>,
+    instance: Instance,
+}
+
+impl Solver {
+    fn load() -> Solver {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("target/wasm32-unknown-unknown/release/advent_of_code_2022.wasm");
+        let wasm = std::fs::read(&path)
+            .unwrap_or_else(|e| panic!("Can't read {}, is it built? {}", path.display(), e));
+        let engine = Engine::default();
+        let module = Module::new(&engine, &wasm[..]).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = Linker::<()>::new(&engine)
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        Solver { store, instance }
+    }
+
+    fn solve(&mut self, day: i32, part: u32, input: &str) -> Result<String, String> {
+        let store = &mut self.store;
+        let memory = self.instance.get_memory(&*store, "memory").unwrap();
+        let alloc = self
+            .instance
+            .get_typed_func::<u32, u32>(&*store, "aoc_alloc")
+            .unwrap();
+        let free = self
+            .instance
+            .get_typed_func::<(u32, u32), ()>(&*store, "aoc_free")
+            .unwrap();
+        let solve = self
+            .instance
+            .get_typed_func::<(i32, u32, u32, u32), i32>(&*store, "aoc_solve")
+            .unwrap();
+        let output_ptr = self
+            .instance
+            .get_typed_func::<(), u32>(&*store, "aoc_output_ptr")
+            .unwrap();
+        let output_len = self
+            .instance
+            .get_typed_func::<(), u32>(&*store, "aoc_output_len")
+            .unwrap();
+
+        let len = input.len() as u32;
+        let ptr = alloc.call(&mut *store, len).unwrap();
+        memory
+            .write(&mut *store, ptr as usize, input.as_bytes())
+            .unwrap();
+        let solved = solve.call(&mut *store, (day, part, ptr, len)).unwrap();
+        free.call(&mut *store, (ptr, len)).unwrap();
+        let mut output = vec![0; output_len.call(&mut *store, ()).unwrap() as usize];
+        let ptr = output_ptr.call(&mut *store, ()).unwrap();
+        memory.read(&*store, ptr as usize, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        if solved == 1 {
+            Ok(output)
+        } else {
+            Err(output)
+        }
+    }
+}
+
+fn example(day: i32) -> (String, Vec<String>) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let input = std::fs::read_to_string(dir.join(format!("{:0>2}.txt", day))).unwrap();
+    let answers = std::fs::read_to_string(dir.join(format!("{:0>2}.answers", day))).unwrap();
+    let answers = answers.lines().map(|a| a.replace("\\n", "\n")).collect();
+    (input, answers)
+}
+
+#[test]
+#[ignore = "needs wasm32-unknown-unknown build"]
+fn test_examples() {
+    let mut solver = Solver::load();
+    for day in [1, 10, 13, 25] {
+        let (input, answers) = example(day);
+        for (part, answer) in answers.iter().enumerate() {
+            assert_eq!(
+                solver.solve(day, part as u32 + 1, &input).as_ref(),
+                Ok(answer),
+                "day {} part {}",
+                day,
+                part + 1
+            );
+        }
+    }
+}
+
+#[test]
+#[ignore = "needs wasm32-unknown-unknown build"]
+fn test_errors() {
+    let mut solver = Solver::load();
+    assert_eq!(
+        solver.solve(26, 1, "").unwrap_err(),
+        "Day 26 isn't solved yet"
+    );
+    assert_eq!(
+        solver.solve(25, 2, "1\n").unwrap_err(),
+        "Day 25 doesn't have a part 2"
+    );
+}